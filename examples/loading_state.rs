@@ -0,0 +1,98 @@
+//! Shows a loading indicator while a titan ron file is loaded in the background.
+//! The texture atlas is built by an async asset loader, so `setup` returns right away and every frame
+//! is rendered while the images are decoded and packed.
+
+#[path = "helpers/animation_helper.rs"]
+mod animation_helper;
+
+use animation_helper::{animate_sprite, AnimationTimer};
+use bevy::prelude::*;
+use bevy_titan::{
+    asset_loader::{self, TitanLoadProgress},
+    SpriteSheetLoaderPlugin,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // prevents blurry sprites
+        .init_state::<MyStates>()
+        .add_plugins(SpriteSheetLoaderPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            update_loading_text.run_if(in_state(MyStates::AssetLoading)),
+        )
+        .add_systems(OnEnter(MyStates::Next), spawn_sprite)
+        .add_systems(Update, animate_sprite.run_if(in_state(MyStates::Next)))
+        .run();
+}
+
+#[derive(Resource)]
+struct MyAssets {
+    texture_atlas: Handle<asset_loader::TextureAtlas>,
+}
+
+#[derive(Component)]
+struct LoadingText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    /* Returns a handle immediately, the texture atlas is built in the background */
+    commands.insert_resource(MyAssets {
+        texture_atlas: asset_server.load("gabe-idle-run.titan.ron"),
+    });
+    commands.spawn(Camera2d);
+    commands.spawn((Text::new("Loading..."), LoadingText));
+}
+
+fn update_loading_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    progress: Res<TitanLoadProgress>,
+    my_assets: Res<MyAssets>,
+    mut next_state: ResMut<NextState<MyStates>>,
+    mut query: Query<(Entity, &mut Text), With<LoadingText>>,
+) {
+    let Ok((entity, mut text)) = query.get_single_mut() else {
+        return;
+    };
+
+    if asset_server.is_loaded_with_dependencies(&my_assets.texture_atlas) {
+        commands.entity(entity).despawn();
+        next_state.set(MyStates::Next);
+    } else if asset_server
+        .load_state(&my_assets.texture_atlas)
+        .is_failed()
+    {
+        text.0 = "Failed to load the texture atlas".to_string();
+    } else if progress.decoded() < progress.images() {
+        text.0 = format!(
+            "Decoding images {}/{}",
+            progress.decoded(),
+            progress.images()
+        );
+    } else {
+        text.0 = "Packing...".to_string();
+    }
+}
+
+fn spawn_sprite(
+    mut commands: Commands,
+    my_assets: Res<MyAssets>,
+    texture_atlases: Res<Assets<asset_loader::TextureAtlas>>,
+) {
+    let texture_atlas = texture_atlases
+        .get(&my_assets.texture_atlas)
+        .expect("The texture atlas is loaded");
+    commands.spawn((
+        texture_atlas.to_sprite(0),
+        Transform::from_scale(Vec3::splat(6.0)),
+        AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
+    ));
+}
+
+#[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
+enum MyStates {
+    #[default]
+    AssetLoading,
+    Next,
+}
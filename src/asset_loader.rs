@@ -4,26 +4,200 @@
 //! Assets with the 'titan' extension can be loaded just like any other asset via the [`AssetServer`](::bevy::asset::AssetServer)
 //! and will yield a [`TextureAtlas`] [`Handle`](::bevy::asset::Handle).
 
-use std::path::Path;
+use std::{
+    borrow::Cow,
+    future::Future,
+    ops::Range,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::Poll,
+    time::Duration,
+};
 
+use base64::{prelude::BASE64_STANDARD, Engine};
 use bevy::{
-    asset::{io::Reader, Asset, AssetLoader, AssetPath, Handle, LoadContext, LoadDirectError},
-    image::{Image, TextureFormatPixelInfo},
-    math::{URect, UVec2},
+    asset::{
+        io::{AssetReaderError, Reader},
+        Asset, AssetLoader, AssetPath, AssetServer, Assets, Handle, LoadContext, LoadDirectError,
+        ReadAssetBytesError,
+    },
+    image::{Image, ImageFormat},
+    log::{debug, info, warn},
+    math::{Rect, URect, UVec2, Vec2},
+    prelude::{FromWorld, Resource, World},
     reflect::Reflect,
     render::{
         render_asset::RenderAssetUsages,
-        render_resource::{Extent3d, TextureDimension},
+        render_resource::{
+            Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension,
+        },
+    },
+    sprite::{
+        BorderRect, Sprite, TextureAtlasBuilder, TextureAtlasBuilderError, TextureAtlasLayout,
     },
-    sprite::{TextureAtlasBuilder, TextureAtlasBuilderError, TextureAtlasLayout},
+    tasks::futures_lite::{future::poll_fn, StreamExt},
+    utils::{HashMap, HashSet},
 };
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::serde::{Titan, TitanEntry, TitanSpriteSheet};
+use crate::{
+    image_utils::{
+        apply_color_key, color_grid_rects, convert, extract_texture_from_rect,
+        extract_texture_from_subpixel_rect, extrude_edges, flip as flip_image, from_dynamic,
+        generate_mipmaps, pad_image, premultiply_alpha, rotate_clockwise, rotate_counterclockwise,
+        scale, snap_alpha, trim,
+    },
+    import::{aseprite, libgdx, load_imported_atlas, texture_packer},
+    manifest::{
+        configuration_fields, deserialize_optional_texture_format, extend, Checksum, Titan,
+        TitanAnimation, TitanChannelMapping, TitanColorSpace, TitanConfiguration,
+        TitanCoordinateOrigin, TitanData, TitanEntry, TitanFormatMismatch, TitanFrame, TitanOutput,
+        TitanPlayback, TitanRotation, TitanScaleFilter, TitanSortOrder, TitanSpriteSheet,
+        TitanSyntax,
+    },
+    metadata::{
+        AnimationClip, AnimationFrame, LayoutSnapshot, SpriteGroup, SpriteSource, SpriteTrim,
+        TextureAtlasAnchors, TextureAtlasAnimation, TextureAtlasAnimations, TextureAtlasGroups,
+        TextureAtlasNames, TextureAtlasRotations, TextureAtlasSlices, TextureAtlasSourceRects,
+        TextureAtlasSources, TextureAtlasStats, TextureAtlasTileables, TextureAtlasTrims,
+        TextureAtlasUvs,
+    },
+};
 
 /// Loader for spritesheet manifest files written in ron. Loads a TextureAtlas asset.
-#[derive(Default)]
-pub struct SpriteSheetLoader;
+///
+/// It is created from the world, e.g. with `app.init_asset_loader::<SpriteSheetLoader>()`, because it needs the
+/// [`AssetServer`] and shares the [`TitanLoadProgress`] resource.
+pub struct SpriteSheetLoader {
+    /// Lists the directories of `glob` entries, which the load context can not.
+    asset_server: AssetServer,
+    /// Shared with the [`TitanLoadProgress`] resource.
+    progress: TitanLoadProgress,
+}
+
+impl FromWorld for SpriteSheetLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            asset_server: world.resource::<AssetServer>().clone(),
+            progress: world
+                .get_resource_or_insert_with(TitanLoadProgress::default)
+                .clone(),
+        }
+    }
+}
+
+/// Progress of all titan files loaded by the [`SpriteSheetLoader`], e.g. to show "decoded 120/500" on a loading screen.
+///
+/// The counters are updated from the loading tasks, so a system can poll this resource at any time.
+/// Apart from [`loading`](Self::loading), they only ever grow.
+/// Once every image is decoded while titan files are still loading, their sprites are being packed.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct TitanLoadProgress(Arc<TitanLoadCounters>);
+
+#[derive(Debug, Default)]
+struct TitanLoadCounters {
+    loading: AtomicUsize,
+    images: AtomicUsize,
+    decoded: AtomicUsize,
+}
+
+impl TitanLoadProgress {
+    /// Number of titan files that are loading right now.
+    pub fn loading(&self) -> usize {
+        self.0.loading.load(Ordering::Relaxed)
+    }
+
+    /// Number of images that have to be decoded for all titan files so far.
+    pub fn images(&self) -> usize {
+        self.0.images.load(Ordering::Relaxed)
+    }
+
+    /// Number of those images that are decoded, successfully or not.
+    pub fn decoded(&self) -> usize {
+        self.0.decoded.load(Ordering::Relaxed)
+    }
+
+    /// Counts a titan file as loading until the returned guard is dropped, also if loading fails.
+    fn start_loading(&self) -> LoadingGuard {
+        self.0.loading.fetch_add(1, Ordering::Relaxed);
+        LoadingGuard(self.clone())
+    }
+}
+
+struct LoadingGuard(TitanLoadProgress);
+
+impl Drop for LoadingGuard {
+    fn drop(&mut self) {
+        self.0 .0.loading.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Settings for [`SpriteSheetLoader`].
+///
+/// Every field that is set overrides the corresponding value of the configuration in the titan ron file.
+/// Use with [`AssetServer::load_with_settings`](::bevy::asset::AssetServer::load_with_settings).
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SpriteSheetLoaderSettings {
+    /// Overrides the starting size of the combined texture atlas.
+    pub initial_size: Option<UVec2>,
+    /// Overrides the maximum size of the combined texture atlas.
+    pub max_size: Option<UVec2>,
+    /// Overrides the texture format of the combined texture atlas.
+    #[serde(default, deserialize_with = "deserialize_optional_texture_format")]
+    pub format: Option<TextureFormat>,
+    /// Overrides the padding between the sprites in the combined texture atlas.
+    pub padding: Option<UVec2>,
+    /// Overrides the maximum number of sprites, e.g. to protect against titan ron files of mods.
+    pub max_frames: Option<u64>,
+    /// Overrides the maximum number of pixels of all sprites combined.
+    pub max_total_area: Option<u64>,
+    /// Label of the combined texture atlas. Default value `texture`.
+    pub texture_label: Option<String>,
+    /// Label of the texture atlas layout. Default value `layout`.
+    pub layout_label: Option<String>,
+    /// Drops the pixels of every page once the atlas is packed, e.g. for servers that only need the layout.
+    /// The texture keeps its size and format, but can not be rendered. Default value false.
+    #[serde(default)]
+    pub layout_only: bool,
+    /// Tags of the entries that are loaded in addition to all untagged entries, e.g. `mobile` or `desktop`.
+    /// Indices are assigned to the loaded entries only, so they differ between sets of active tags.
+    #[serde(default)]
+    pub active_tags: Vec<String>,
+}
+
+impl SpriteSheetLoaderSettings {
+    fn apply(&self, configuration: &mut TitanConfiguration) {
+        if let Some(initial_size) = self.initial_size {
+            configuration.initial_size = initial_size;
+        }
+        if let Some(max_size) = self.max_size {
+            configuration.max_size = max_size;
+        }
+        if let Some(format) = self.format {
+            configuration.format = format;
+        }
+        if let Some(padding) = self.padding {
+            configuration.padding = padding;
+        }
+        if let Some(max_frames) = self.max_frames {
+            configuration.max_frames = max_frames;
+        }
+        if let Some(max_total_area) = self.max_total_area {
+            configuration.max_total_area = max_total_area;
+        }
+    }
+
+    pub(crate) fn labels(&self) -> AtlasLabels<'_> {
+        AtlasLabels {
+            texture: self.texture_label.as_deref().unwrap_or(TEXTURE_LABEL),
+            layout: self.layout_label.as_deref().unwrap_or(LAYOUT_LABEL),
+        }
+    }
+}
 
 /// Possible errors that can be produced by [`SpriteSheetLoader`].
 #[non_exhaustive]
@@ -35,33 +209,275 @@ pub enum SpriteSheetLoaderError {
     /// A [RonSpannedError](ron::error::SpannedError).
     #[error("Could not parse RON: {0}")]
     RonSpannedError(#[from] ron::error::SpannedError),
-    /// A [`LoadDirectError``].
+    /// A [`LoadDirectError`].
     #[error("Could not load: {0}")]
-    LoadDirectError(#[from] LoadDirectError),
-    /// A NotAnImageError.
-    #[error("Loading from {0} does not provide Image")]
-    NotAnImageError(String),
+    LoadDirectError(Box<LoadDirectError>),
+    /// A [`ReadAssetBytesError`].
+    #[error("Could not read: {0}")]
+    ReadAssetBytesError(#[from] ReadAssetBytesError),
+    /// An [`ImageError`](image::ImageError) for the png image at the given path.
+    #[error("Could not decode png image {0}: {1}")]
+    PngError(String, image::ImageError),
+    /// A [JsonError](serde_json::Error).
+    #[error("Could not parse JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+    /// A NotAnImageError with the path and the reason why it is not an image.
+    #[error("Loading from {0} does not provide Image, because {1}")]
+    NotAnImageError(String, String),
+    /// The image format of a source image is known, but the corresponding bevy feature is not enabled.
+    #[error("Image {path} can not be decoded, because the image format {ext} is not enabled, consider enabling the corresponding bevy feature")]
+    UnsupportedImageFormat {
+        /// Path of the image.
+        path: String,
+        /// Extension of the image.
+        ext: String,
+    },
     /// A [`TextureAtlasBuilderError`].
     #[error("TextureAtlasBuilderError: {0}")]
     TextureAtlasBuilderError(#[from] TextureAtlasBuilderError),
     /// A NoEntriesError
     #[error("No entries were found")]
     NoEntriesError,
+    /// A [`NotEnoughSpaceError`].
+    #[error("NotEnoughSpaceError: {0}")]
+    NotEnoughSpaceError(#[from] NotEnoughSpaceError),
     /// An [`InvalidRectError`].
     #[error("InvalidRectError: {0}")]
     InvalidRectError(#[from] InvalidRectError),
+    /// A CyclicExtendsError.
+    #[error("Titan ron file {0} is extended in a cycle")]
+    CyclicExtendsError(String),
+    /// A LimitExceeded error.
+    #[error("{0} is {1}, which exceeds the limit of {2}")]
+    LimitExceeded(&'static str, u64, u64),
+    /// A RepackError.
+    #[error("Without repack only a single entry is supported, but there are {0}")]
+    RepackError(usize),
+    /// An EntrySourceError.
+    #[error("Entry {0} must have exactly one of a path, inline data, a texture atlas to pull frames from or a glob")]
+    EntrySourceError(usize),
+    /// An UnresolvedAtlasError.
+    #[error("Entry {0} pulls in frames of another texture atlas, which is only supported by the SpriteSheetLoader")]
+    UnresolvedAtlasError(usize),
+    /// An UnresolvedGlobError.
+    #[error("Entry {0} expands a glob, which is only supported by the SpriteSheetLoader")]
+    UnresolvedGlobError(usize),
+    /// An InvalidGlobError.
+    #[error("Glob {0} may only contain wildcards in its file name")]
+    InvalidGlobError(String),
+    /// An EmptyGlobError.
+    #[error("Glob {0} does not match any image")]
+    EmptyGlobError(String),
+    /// A CyclicAtlasError.
+    #[error("Titan ron file {0} pulls in frames of itself")]
+    CyclicAtlasError(String),
+    /// A TransformedFrameError.
+    #[error("Frame {0} of texture atlas {1} is transformed and can not be pulled in from its source image")]
+    TransformedFrameError(String, String),
+    /// An UnknownFrameError.
+    #[error("Texture atlas {1} has no frame named {0}")]
+    UnknownFrameError(String, String),
+    /// An InlineFrameError.
+    #[error("Frame {0} of texture atlas {1} is not extracted from an image file")]
+    InlineFrameError(String, String),
+    /// An [`InlineDataError`].
+    #[error("InlineDataError: {0}")]
+    InlineDataError(#[from] InlineDataError),
     /// A SizeMismatchError.
     #[error("Configured initial size {0} is bigger than max size {1}")]
     SizeMismatchError(UVec2, UVec2),
+    /// An entry declares a color space that differs from the one of the texture format of the combined texture atlas.
+    #[error("Entry {index} is declared as {color_space:?}, but the combined texture atlas uses texture format {format:?}, pack it with a texture format of the same color space instead")]
+    ColorSpaceMismatch {
+        /// Index of the entry.
+        index: usize,
+        /// Declared color space of the entry.
+        color_space: TitanColorSpace,
+        /// Texture format of the combined texture atlas.
+        format: TextureFormat,
+    },
+    /// A single sprite does not fit into `max_size`, so it can never be packed.
+    #[error("Sprite of size {size} from image {path} does not fit into max size {max_size} with padding, consider raising max_size or scaling it down")]
+    SourceTooLarge {
+        /// Path of the image the sprite is extracted from.
+        path: String,
+        /// Size of the sprite after scaling and trimming.
+        size: UVec2,
+        /// Maximum size of the combined texture atlas.
+        max_size: UVec2,
+    },
+    /// A FormatConversionError.
+    #[error("Can not convert image {0} from texture format {1:?} to {2:?}")]
+    FormatConversionError(String, TextureFormat, TextureFormat),
+    /// A sprite does not have the texture format of the combined texture atlas
+    /// and `on_format_mismatch` is [`TitanFormatMismatch::Error`] or `auto_format_conversion` of its entry is disabled.
+    #[error("Image {path} has texture format {found:?}, but the combined texture atlas uses {expected:?}")]
+    FormatMismatch {
+        /// Texture format of the combined texture atlas.
+        expected: TextureFormat,
+        /// Texture format of the sprite.
+        found: TextureFormat,
+        /// Path of the image the sprite was extracted from.
+        path: String,
+    },
+    /// A ScaleError.
+    #[error("Can not scale image {0} of texture format {1:?} with filter {2:?}")]
+    ScaleError(String, TextureFormat, TitanScaleFilter),
+    /// A SubpixelFormatError.
+    #[error("Can not extract subpixel rects from image {0} of texture format {1:?}, only 8 bit unorm formats are supported")]
+    SubpixelFormatError(String, TextureFormat),
+    /// An InvalidFrameRateError.
+    #[error("Frame rate {0} of animation {1} must be positive")]
+    InvalidFrameRateError(f32, String),
+    /// An UnknownAnimationFrameError.
+    #[error("Animation {0} references sprite {1}, but there is no sprite with this name")]
+    UnknownAnimationFrameError(String, String),
+    /// An InvalidScaleError.
+    #[error("Scale {0} of entry {1} must be positive")]
+    InvalidScaleError(f32, usize),
+    /// An EmptyGridError.
+    #[error("Homogeneous sprite sheet of entry {2} has {0} columns and {1} rows, but needs at least one of each")]
+    EmptyGridError(u32, u32, usize),
+    /// An InvalidSubpixelRectError.
+    #[error("Rect at {0} with size {1} of entry {2} must be finite, must not be negative and must not be empty")]
+    InvalidSubpixelRectError(Vec2, Vec2, usize),
+    /// An InvalidDefaultIndexError.
+    #[error("Default index {0} is out of range for {1} sprites")]
+    InvalidDefaultIndexError(usize, usize),
+    /// A ZeroAlignmentError.
+    #[error("Configured alignment must not be zero")]
+    ZeroAlignmentError,
+    /// A ZeroSizeError.
+    #[error("Configured initial size {0} and max size {1} must not have a zero component")]
+    ZeroSizeError(UVec2, UVec2),
+    /// An ArraySizeError.
+    #[error("Sprite {0} has size {1}, but the array texture has layers of size {2}")]
+    ArraySizeError(usize, UVec2, UVec2),
+    /// An ArrayRepackError.
+    #[error("Array output requires repack")]
+    ArrayRepackError,
+    /// A MipmapFormatError.
+    #[error("Can not generate mipmaps for texture format {0:?}")]
+    MipmapFormatError(TextureFormat),
+    /// A [`DuplicateNameError`].
+    #[error("DuplicateNameError: {0}")]
+    DuplicateNameError(#[from] DuplicateNameError),
+    /// A homogeneous sprite sheet does not fit into its image.
+    #[error("Homogeneous sprite sheet of entry {index} with {columns}x{rows} tiles of size {tile_size} requires an image of at least {required_size}, but image {path} has size {image_size}")]
+    GridOutOfBounds {
+        /// Size of a single tile.
+        tile_size: UVec2,
+        /// Number of columns.
+        columns: u32,
+        /// Number of rows.
+        rows: u32,
+        /// Size of the image.
+        image_size: UVec2,
+        /// Size the image needs to have at least, including offset and padding.
+        required_size: UVec2,
+        /// Path of the image.
+        path: String,
+        /// Index of the entry.
+        index: usize,
+    },
+    /// A ColorGridError.
+    #[error("Can not detect a color grid in image {0} of texture format {1:?}")]
+    ColorGridError(String, TextureFormat),
+    /// An [`InvalidGridError`].
+    #[error("InvalidGridError: {0}")]
+    InvalidGridError(#[from] InvalidGridError),
+    /// An [`InvalidAnimationError`].
+    #[error("InvalidAnimationError: {0}")]
+    InvalidAnimationError(#[from] InvalidAnimationError),
+    /// An [`InvalidNineSliceError`].
+    #[error("InvalidNineSliceError: {0}")]
+    InvalidNineSliceError(#[from] InvalidNineSliceError),
+    /// A [`LibGdxAtlasError`].
+    #[error("LibGdxAtlasError: {0}")]
+    LibGdxAtlasError(#[from] LibGdxAtlasError),
+}
+
+impl From<LoadDirectError> for SpriteSheetLoaderError {
+    fn from(value: LoadDirectError) -> Self {
+        Self::LoadDirectError(Box::new(value))
+    }
 }
 
 /// InvalidRectError.
 #[derive(Debug, Error)]
-#[error("Rect with min {0} and max {1} is invalid for image {2}")]
-pub struct InvalidRectError(UVec2, UVec2, String);
+#[error("Rect with min {0} and max {1} is invalid for image {2} of entry {3}")]
+pub struct InvalidRectError(
+    pub(crate) UVec2,
+    pub(crate) UVec2,
+    pub(crate) String,
+    pub(crate) usize,
+);
+
+/// InlineDataError.
+#[derive(Debug, Error)]
+#[error("Inline data of entry {0} is invalid: {1}")]
+pub struct InlineDataError(usize, String);
+
+/// NotEnoughSpaceError.
+#[derive(Debug, Error)]
+#[error("{0} sprites with a total area of {1} pixels do not fit into max size {2}, consider raising max_size")]
+pub struct NotEnoughSpaceError(usize, u64, UVec2);
+
+/// InvalidGridError.
+#[derive(Debug, Error)]
+#[error("Image {2} of entry {3} with size {0} can not be evenly divided into tiles of size {1} with the given padding and offset")]
+pub struct InvalidGridError(UVec2, UVec2, String, usize);
+
+/// DuplicateNameError.
+#[derive(Debug, Error)]
+#[error("Name {0} is used more than once")]
+pub struct DuplicateNameError(pub(crate) String);
+
+/// InvalidAnimationError.
+#[derive(Debug, Error)]
+#[error("Animation {0} references sprite {1}, but there are only {2} sprites")]
+pub struct InvalidAnimationError(String, usize, usize);
+
+/// Nine-slice insets of an entry do not fit into one of its sprites.
+#[derive(Debug, Error)]
+#[error("Nine-slice insets of image {0} of entry {1} do not fit into sprite of size {2}")]
+pub struct InvalidNineSliceError(String, usize, UVec2);
+
+/// The LibGDX atlas file is malformed at the given line.
+#[derive(Debug, Error)]
+#[error("Line {0}: {1}")]
+pub struct LibGdxAtlasError(pub(crate) usize, pub(crate) String);
 
 /// File extension for spritesheet manifest files written in ron.
-pub const FILE_EXTENSIONS: &[&str] = &["titan.ron", "titan"];
+pub const FILE_EXTENSIONS: &[&str] = &[
+    "titan.ron",
+    "titan",
+    TITAN_JSON_FILE_EXTENSION,
+    TEXTURE_PACKER_FILE_EXTENSION,
+    ASEPRITE_FILE_EXTENSION,
+    LIBGDX_FILE_EXTENSION,
+];
+
+/// Default label of the combined texture atlas.
+const TEXTURE_LABEL: &str = "texture";
+
+/// Default label of the texture atlas layout.
+const LAYOUT_LABEL: &str = "layout";
+
+/// Anchor of sprites without an explicit anchor, the center like for a bevy sprite.
+pub(crate) const DEFAULT_ANCHOR: Vec2 = Vec2::splat(0.5);
+
+/// Extension of titan files written in json instead of ron.
+pub const TITAN_JSON_FILE_EXTENSION: &str = "titan.json";
+
+/// Extension of TexturePacker JSON files, which are imported as is instead of being packed.
+pub const TEXTURE_PACKER_FILE_EXTENSION: &str = "tpjson";
+
+/// Extension of Aseprite JSON files, which are imported as is instead of being packed.
+pub const ASEPRITE_FILE_EXTENSION: &str = "aseprite.json";
+
+/// Extension of LibGDX TextureAtlas files, which are imported as is instead of being packed.
+pub const LIBGDX_FILE_EXTENSION: &str = "atlas";
 
 /// TextureAtlas Asset
 #[derive(Debug, Asset, Reflect)]
@@ -70,83 +486,190 @@ pub struct TextureAtlas {
     pub texture: Handle<Image>,
     /// Texture Atlas Layout
     pub layout: Handle<TextureAtlasLayout>,
+    /// Number of sprites in the layout, e.g. to wrap an animation index without accessing the layout.
+    pub frame_count: usize,
+    /// Size of the texture in pixels, e.g. to normalize UVs without accessing the texture.
+    pub atlas_size: UVec2,
+    /// Names of the sprites, also available with the `names` label.
+    pub names: Handle<TextureAtlasNames>,
+    /// Named animations, also available with the `animations` label.
+    pub animations: Handle<TextureAtlasAnimations>,
+    /// All pages of the texture atlas, starting with `texture`. Only `allow_multiple_pages` results in more than one.
+    pub pages: Vec<Handle<Image>>,
+    /// Index into `pages` for every sprite in the layout.
+    pub page_indices: Vec<usize>,
+    /// `version` of the titan ron file.
+    pub version: Option<String>,
+    /// Deterministic hash of the loaded file, every extended titan ron file and every image it reads.
+    /// It only changes if one of them changes, e.g. to invalidate a cache of the packed atlas.
+    /// Use [`checksum`](crate::export::checksum) to compute it offline. `0` if not loaded from a file.
+    pub checksum: u64,
+    /// Index of the sprite that [`clamp_index`](Self::clamp_index) falls back to, `default_index` of the configuration.
+    pub default_index: Option<usize>,
+}
+
+impl TextureAtlas {
+    /// The page the sprite at `index` lives on.
+    pub fn page(&self, index: usize) -> &Handle<Image> {
+        self.page_indices
+            .get(index)
+            .and_then(|&page_index| self.pages.get(page_index))
+            .unwrap_or(&self.texture)
+    }
+
+    /// `index` if it is in range, otherwise `default_index` or, without one or if it is out of range as well,
+    /// the last index.
+    ///
+    /// Use this for indices that may be outdated, e.g. from a save file, instead of panicking.
+    /// An empty texture atlas has no valid index, so 0 is returned.
+    pub fn clamp_index(&self, index: usize) -> usize {
+        if index < self.frame_count {
+            index
+        } else {
+            self.default_index
+                .filter(|&default_index| default_index < self.frame_count)
+                .unwrap_or(self.frame_count.saturating_sub(1))
+        }
+    }
+
+    /// Snapshot of the layout and the page of every sprite, `None` while the layout is not loaded.
+    pub fn layout_snapshot(&self, layouts: &Assets<TextureAtlasLayout>) -> Option<LayoutSnapshot> {
+        let layout = layouts.get(&self.layout)?;
+
+        Some(LayoutSnapshot {
+            page_indices: self.page_indices.clone(),
+            ..LayoutSnapshot::from(layout)
+        })
+    }
+
+    /// Creates a [`Sprite`] that displays the sprite at `index` of this texture atlas.
+    pub fn to_sprite(&self, index: usize) -> Sprite {
+        Sprite::from_atlas_image(
+            self.page(index).clone(),
+            bevy::sprite::TextureAtlas {
+                layout: self.layout.clone(),
+                index,
+            },
+        )
+    }
 }
 
 impl AssetLoader for SpriteSheetLoader {
     type Asset = TextureAtlas;
-    type Settings = ();
+    type Settings = SpriteSheetLoaderSettings;
     type Error = SpriteSheetLoaderError;
 
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
+        let _loading = self.progress.start_loading();
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
-        let titan = ron::de::from_bytes::<Titan>(&bytes)?;
+        let mut checksum = Checksum::default();
+        checksum.update(&bytes);
+        let imported_atlas = if has_extension(load_context.path(), TEXTURE_PACKER_FILE_EXTENSION) {
+            Some(texture_packer::parse(&bytes)?)
+        } else if has_extension(load_context.path(), ASEPRITE_FILE_EXTENSION) {
+            Some(aseprite::parse(&bytes)?)
+        } else if has_extension(load_context.path(), LIBGDX_FILE_EXTENSION) {
+            Some(libgdx::parse(&String::from_utf8_lossy(&bytes))?)
+        } else {
+            None
+        };
+        if let Some(imported_atlas) = imported_atlas {
+            return load_imported_atlas(
+                imported_atlas,
+                load_context,
+                settings,
+                checksum,
+                &self.progress,
+            )
+            .await;
+        }
 
-        let configuration = titan.configuration;
-        if configuration.max_size.x < configuration.initial_size.x
-            || configuration.max_size.y < configuration.initial_size.y
-        {
-            return Err(SpriteSheetLoaderError::SizeMismatchError(
-                configuration.initial_size,
-                configuration.max_size,
-            ));
+        let syntax = TitanSyntax::from_path(load_context.path());
+        let mut titan = syntax.deserialize::<Titan>(&bytes)?;
+        if titan.extends.is_some() {
+            /* Reading through the load context also reloads the titan ron file if an extended one changes */
+            let mut fields = configuration_fields(&bytes, syntax)?;
+            let mut visited = vec![load_context.path().to_string_lossy().into_owned()];
+            let mut extends = titan.extends.clone();
+            while let Some(path) = extends {
+                if visited.contains(&path) {
+                    return Err(SpriteSheetLoaderError::CyclicExtendsError(path));
+                }
+                let base_bytes = load_context
+                    .read_asset_bytes(AssetPath::from_path(Path::new(&path)))
+                    .await?;
+                checksum.update(&base_bytes);
+                extends = extend(&mut titan.configuration, &mut fields, &path, &base_bytes)?;
+                visited.push(path);
+            }
         }
+        settings.apply(&mut titan.configuration);
+        filter_tags(&mut titan, &settings.active_tags);
+        apply_base_path(&mut titan);
+        resolve_glob_entries(&mut titan.textures, &self.asset_server, load_context).await?;
+        resolve_atlas_entries(&mut titan.textures, load_context, &mut checksum).await?;
+        validate(&titan)?;
 
-        let titan_entries = titan.textures;
-        if titan_entries.is_empty() {
-            return Err(SpriteSheetLoaderError::NoEntriesError);
+        /* Read every distinct image only once. Reading through the load context registers each image
+         * as a loader dependency, so the titan ron file is reloaded whenever one of them changes. */
+        let paths = source_paths(&titan.textures);
+        let mut sources = Vec::with_capacity(paths.len());
+        let mut missing = Vec::new();
+        for path in paths {
+            let image_asset_path = AssetPath::from_path(Path::new(path));
+            let bytes = match load_context.read_asset_bytes(image_asset_path).await {
+                Err(ReadAssetBytesError::AssetReaderError(AssetReaderError::NotFound(_)))
+                    if titan.configuration.ignore_missing =>
+                {
+                    missing.push(path.to_string());
+                    continue;
+                }
+                result => result?,
+            };
+            checksum.update(path.as_bytes());
+            checksum.update(&bytes);
+            sources.push((path, bytes));
         }
 
-        let images_len = titan_entries.iter().fold(0, |acc, titan_entry| {
-            acc + match &titan_entry.sprite_sheet {
-                TitanSpriteSheet::None => 1,
-                TitanSpriteSheet::Homogeneous { columns, rows, .. } => (columns * rows) as usize,
-                TitanSpriteSheet::Heterogeneous(vec) => vec.len(),
-            }
-        });
-        let mut images = Vec::with_capacity(images_len);
-        for titan_entry in titan_entries.into_iter() {
-            /* Load the image */
-            let titan_entry_path = titan_entry.path.clone();
-            let image_asset_path = AssetPath::from_path(Path::new(&titan_entry_path));
-            let image = load_context
-                .loader()
-                .immediate()
-                .load(image_asset_path)
-                .await?;
-
-            /* Get and insert all rects */
-            push_textures(&mut images, titan_entry, image.take())?;
-        }
-
-        let mut texture_atlas_builder = TextureAtlasBuilder::default();
-        texture_atlas_builder
-            .initial_size(configuration.initial_size)
-            .max_size(configuration.max_size)
-            .format(configuration.format)
-            .auto_format_conversion(configuration.auto_format_conversion)
-            .padding(configuration.padding);
-        for image in &images {
-            texture_atlas_builder.add_texture(None, image);
-        }
-        let (texture_atlas_layout, _, atlas_texture) = texture_atlas_builder.build()?;
-
-        let atlas_texture_handle =
-            load_context.add_loaded_labeled_asset("texture", atlas_texture.into());
-        let texture_atlas_layout_handle =
-            load_context.add_loaded_labeled_asset("layout", texture_atlas_layout.into());
+        /* Decode all images, grayscale pngs stay single channel for a single channel atlas */
+        let single_channel = titan.configuration.format == TextureFormat::R8Unorm;
+        let mut source_images = HashMap::with_capacity(sources.len());
+        for ((path, _), image) in sources
+            .iter()
+            .zip(decode_images(load_context, &sources, single_channel, &self.progress).await)
+        {
+            source_images.insert(path.to_string(), image?);
+        }
+        /* Free the encoded images before any sprite is extracted */
+        drop(sources);
+        skip_missing(&mut titan, &missing)?;
 
-        let texture_atlas = TextureAtlas {
-            texture: atlas_texture_handle,
-            layout: texture_atlas_layout_handle,
-        };
+        let mut packed_atlas = pack_titan(titan, |path| {
+            Ok(source_images
+                .remove(path)
+                .expect("Every path of the manifest has a decoded image"))
+        })?;
+        packed_atlas.checksum = checksum.finish();
+        debug!(
+            "{}: packed with checksum {:016x}",
+            load_context.path().display(),
+            packed_atlas.checksum
+        );
+        if settings.layout_only {
+            packed_atlas.drop_pixels();
+        }
 
-        Ok(texture_atlas)
+        Ok(add_labeled_assets(
+            load_context,
+            packed_atlas,
+            settings.labels(),
+        ))
     }
 
     fn extensions(&self) -> &[&str] {
@@ -154,89 +677,5114 @@ impl AssetLoader for SpriteSheetLoader {
     }
 }
 
-fn push_textures(
-    images: &mut Vec<Image>,
-    titan_entry: TitanEntry,
-    texture: Image,
-) -> Result<(), InvalidRectError> {
-    match titan_entry.sprite_sheet {
-        TitanSpriteSheet::None => {
-            images.push(texture);
+/// Replaces every entry with a glob by one entry per matching image, sorted by path and named after its file stem.
+///
+/// Wildcards, `*` for any number and `?` for a single character, are only supported in the file name.
+/// Unlike the matching images, the directory is not a dependency, so adding an image does not reload the titan file.
+async fn resolve_glob_entries(
+    titan_entries: &mut Vec<TitanEntry>,
+    asset_server: &AssetServer,
+    load_context: &LoadContext<'_>,
+) -> Result<(), SpriteSheetLoaderError> {
+    if titan_entries
+        .iter()
+        .all(|titan_entry| titan_entry.glob.is_none())
+    {
+        return Ok(());
+    }
+
+    let source = asset_server
+        .get_source(load_context.asset_path().source())
+        .map_err(ReadAssetBytesError::from)?;
+    let mut resolved_entries = Vec::with_capacity(titan_entries.len());
+    for (index, mut titan_entry) in std::mem::take(titan_entries).into_iter().enumerate() {
+        let Some(glob) = titan_entry.glob.take() else {
+            resolved_entries.push(titan_entry);
+            continue;
+        };
+        if !titan_entry.path.is_empty()
+            || titan_entry.data.is_some()
+            || titan_entry.from_atlas.is_some()
+        {
+            return Err(SpriteSheetLoaderError::EntrySourceError(index));
+        }
+        let glob_path = Path::new(&glob);
+        let directory = glob_path.parent().unwrap_or(Path::new(""));
+        let pattern = glob_path
+            .file_name()
+            .map(|pattern| pattern.to_string_lossy())
+            .unwrap_or_default();
+        if directory.to_string_lossy().contains(['*', '?']) {
+            return Err(SpriteSheetLoaderError::InvalidGlobError(glob));
         }
-        TitanSpriteSheet::Homogeneous {
-            tile_size,
-            columns,
-            rows,
-            padding,
-            offset,
-        } => {
-            for i in 0..rows {
-                for j in 0..columns {
-                    let min = UVec2::new(j, i) * tile_size
-                        + offset
-                        + (UVec2::new(1 + 2 * j, 1 + 2 * i) * padding);
-                    let max = min + tile_size;
-                    let rect = URect::from_corners(min, max);
 
-                    let image = extract_texture_from_rect(&texture, rect)?;
+        let mut paths = source
+            .reader()
+            .read_directory(directory)
+            .await
+            .map_err(ReadAssetBytesError::from)?
+            .filter(|path| {
+                path.file_name()
+                    .is_some_and(|file_name| glob_matches(&pattern, &file_name.to_string_lossy()))
+            })
+            .collect::<Vec<_>>()
+            .await;
+        paths.sort();
+        let mut matched = false;
+        for path in paths {
+            if source
+                .reader()
+                .is_directory(&path)
+                .await
+                .map_err(ReadAssetBytesError::from)?
+            {
+                continue;
+            }
+            matched = true;
+            resolved_entries.push(TitanEntry {
+                name: path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned()),
+                name_prefix: None,
+                path: path.to_string_lossy().into_owned(),
+                sprite_sheet: TitanSpriteSheet::None,
+                ..titan_entry.clone()
+            });
+        }
+        if !matched {
+            return Err(SpriteSheetLoaderError::EmptyGlobError(glob));
+        }
+    }
+    *titan_entries = resolved_entries;
 
-                    images.push(image);
-                }
+    Ok(())
+}
+
+/// Whether `name` matches `pattern`, in which `*` matches any number of characters and `?` a single one.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    /* Greedy matching that backtracks to the last star */
+    let (mut pattern_index, mut name_index) = (0, 0);
+    let mut star = None;
+    while name_index < name.len() {
+        match pattern.get(pattern_index) {
+            Some('*') => {
+                star = Some((pattern_index, name_index));
+                pattern_index += 1;
+            }
+            Some(&character) if character == '?' || character == name[name_index] => {
+                pattern_index += 1;
+                name_index += 1;
             }
+            _ => match star {
+                Some((star_index, star_name_index)) => {
+                    pattern_index = star_index + 1;
+                    name_index = star_name_index + 1;
+                    star = Some((star_index, name_index));
+                }
+                None => return false,
+            },
         }
-        TitanSpriteSheet::Heterogeneous(rects) => {
-            for (position, size) in rects {
-                let min = position;
-                let max = min + size;
-                let rect = URect::from_corners(min, max);
+    }
 
-                let image = extract_texture_from_rect(&texture, rect)?;
+    pattern[pattern_index..]
+        .iter()
+        .all(|&character| character == '*')
+}
 
-                images.push(image);
+/// Replaces every entry that pulls in frames of another texture atlas with one entry per frame, cropped to the
+/// region of the source image the frame was extracted from.
+///
+/// Loading the other texture atlas through the load context also reloads this one whenever it changes.
+async fn resolve_atlas_entries(
+    titan_entries: &mut Vec<TitanEntry>,
+    load_context: &mut LoadContext<'_>,
+    checksum: &mut Checksum,
+) -> Result<(), SpriteSheetLoaderError> {
+    if titan_entries
+        .iter()
+        .all(|titan_entry| titan_entry.from_atlas.is_none())
+    {
+        return Ok(());
+    }
+
+    let mut resolved_entries = Vec::with_capacity(titan_entries.len());
+    for (index, mut titan_entry) in std::mem::take(titan_entries).into_iter().enumerate() {
+        let Some(atlas_path) = titan_entry.from_atlas.take() else {
+            resolved_entries.push(titan_entry);
+            continue;
+        };
+        if !titan_entry.path.is_empty() || titan_entry.data.is_some() {
+            return Err(SpriteSheetLoaderError::EntrySourceError(index));
+        }
+        check_atlas_cycle(&atlas_path, load_context).await?;
+
+        let texture_atlas = load_context
+            .loader()
+            .immediate()
+            .load::<TextureAtlas>(AssetPath::from_path(Path::new(&atlas_path)))
+            .await?;
+        checksum.update(&texture_atlas.get().checksum.to_le_bytes());
+        let labeled = |label: &'static str| {
+            texture_atlas
+                .get_labeled(label)
+                .expect("Every texture atlas has all labeled assets")
+        };
+        let names = labeled("names")
+            .get::<TextureAtlasNames>()
+            .expect("The names asset has the names label");
+        let source_rects = labeled("source_rects")
+            .get::<TextureAtlasSourceRects>()
+            .expect("The source rects asset has the source_rects label");
+
+        for frame in std::mem::take(&mut titan_entry.frames) {
+            let source = names
+                .get(&frame)
+                .map(|&index| &source_rects[index])
+                .ok_or_else(|| {
+                    SpriteSheetLoaderError::UnknownFrameError(frame.clone(), atlas_path.clone())
+                })?;
+            /* Inline data and images of a builder are only known to the other texture atlas */
+            if source.path.starts_with('<') {
+                return Err(SpriteSheetLoaderError::InlineFrameError(
+                    frame,
+                    atlas_path.clone(),
+                ));
             }
+            /* Only the source region is known, not how the other texture atlas transformed it */
+            if source.transformed {
+                return Err(SpriteSheetLoaderError::TransformedFrameError(
+                    frame,
+                    atlas_path.clone(),
+                ));
+            }
+            resolved_entries.push(TitanEntry {
+                path: source.path.clone(),
+                name: Some(frame),
+                name_prefix: None,
+                source_rect: Some(source.rect),
+                sprite_sheet: TitanSpriteSheet::None,
+                ..titan_entry.clone()
+            });
+        }
+    }
+    *titan_entries = resolved_entries;
+
+    Ok(())
+}
+
+/// Follows the `from_atlas` entries of `atlas_path` and of every titan file it pulls in frames of,
+/// so a cycle fails instead of loading the same titan files over and over.
+async fn check_atlas_cycle(
+    atlas_path: &str,
+    load_context: &mut LoadContext<'_>,
+) -> Result<(), SpriteSheetLoaderError> {
+    let mut chain = vec![load_context.path().to_string_lossy().into_owned()];
+    let mut pending = vec![(atlas_path.to_string(), 1)];
+    while let Some((path, depth)) = pending.pop() {
+        chain.truncate(depth);
+        if chain.contains(&path) {
+            return Err(SpriteSheetLoaderError::CyclicAtlasError(path));
+        }
+        /* Imported atlases can not pull in frames */
+        let asset_path = Path::new(&path);
+        if [
+            TEXTURE_PACKER_FILE_EXTENSION,
+            ASEPRITE_FILE_EXTENSION,
+            LIBGDX_FILE_EXTENSION,
+        ]
+        .iter()
+        .any(|extension| has_extension(asset_path, extension))
+        {
+            continue;
         }
+        let bytes = load_context
+            .read_asset_bytes(AssetPath::from_path(asset_path))
+            .await?;
+        let titan = TitanSyntax::from_path(asset_path).deserialize::<Titan>(&bytes)?;
+        pending.extend(
+            titan
+                .textures
+                .into_iter()
+                .filter_map(|titan_entry| titan_entry.from_atlas)
+                .map(|from_atlas| (from_atlas, depth + 1)),
+        );
+        chain.push(path);
     }
 
     Ok(())
 }
 
-fn extract_texture_from_rect(image: &Image, rect: URect) -> Result<Image, InvalidRectError> {
-    if (rect.max.x > image.size().x) || (rect.max.y > image.size().y) {
-        Err(InvalidRectError(rect.min, rect.max, String::from("Test")))
+/// Packs all images like [`build_rotated_texture_atlas`]. If they do not fit and multiple pages are allowed,
+/// every page is filled with as many of the remaining images as fit, in order.
+fn build_pages(
+    configuration: &TitanConfiguration,
+    images: &[Image],
+) -> Result<Vec<(TextureAtlasLayout, Image, Vec<bool>)>, TextureAtlasBuilderError> {
+    let images_len = images.len();
+    let pages = if configuration.allow_multiple_pages {
+        build_multiple_pages(configuration, images)?
     } else {
-        let format_size = image.texture_descriptor.format.pixel_size();
-        let rect_size = UVec2::new(rect.max.x - rect.min.x, rect.max.y - rect.min.y);
-        let mut data: Vec<u8> = vec![0; (rect_size.x * rect_size.y) as usize * format_size];
+        vec![build_rotated_texture_atlas(configuration, images)?]
+    };
 
-        for i in 0..rect_size.y {
-            let data_begin = (rect_size.x * i) as usize * format_size;
-            let data_end = data_begin + rect_size.x as usize * format_size;
-            let texture_atlas_rect_begin = (rect.min.x as usize
-                + (rect.min.y + i) as usize * image.width() as usize)
-                * format_size;
-            let texture_atlas_rect_end =
-                texture_atlas_rect_begin + rect_size.x as usize * format_size;
+    /* Only log for the final pages, not for every trial build. Deduplicated sprites share a rect. */
+    if configuration.deduplicate {
+        let deduplicated = pages
+            .iter()
+            .map(|(layout, _, _)| {
+                layout.textures.len() - layout.textures.iter().collect::<HashSet<_>>().len()
+            })
+            .sum::<usize>();
+        info!("Deduplicated {} of {} sprites", deduplicated, images_len);
+    }
+
+    Ok(pages)
+}
 
-            data[data_begin..data_end]
-                .copy_from_slice(&image.data[texture_atlas_rect_begin..texture_atlas_rect_end]);
+/// Fills every page with as many of the remaining images as fit, in order.
+fn build_multiple_pages(
+    configuration: &TitanConfiguration,
+    images: &[Image],
+) -> Result<Vec<(TextureAtlasLayout, Image, Vec<bool>)>, TextureAtlasBuilderError> {
+    let mut pages = Vec::new();
+    let mut remaining = images;
+    while !remaining.is_empty() {
+        match build_rotated_texture_atlas(configuration, remaining) {
+            Ok(page) => {
+                pages.push(page);
+                break;
+            }
+            Err(TextureAtlasBuilderError::NotEnoughSpace) => {}
+            Err(error) => return Err(error),
         }
 
-        let image = Image::new(
-            Extent3d {
-                width: rect_size.x,
-                height: rect_size.y,
-                depth_or_array_layers: 1,
-            },
-            TextureDimension::D2,
-            data,
-            image.texture_descriptor.format,
-            RenderAssetUsages::MAIN_WORLD,
-        );
-        Ok(image)
+        /* Search the most images that still fit, at least one has to */
+        let mut page = None;
+        let (mut low, mut high) = (1, remaining.len() - 1);
+        while low <= high {
+            let len = (low + high) / 2;
+            match build_rotated_texture_atlas(configuration, &remaining[..len]) {
+                Ok(fitting_page) => {
+                    page = Some((len, fitting_page));
+                    low = len + 1;
+                }
+                Err(TextureAtlasBuilderError::NotEnoughSpace) => high = len - 1,
+                Err(error) => return Err(error),
+            }
+        }
+        let (len, page) = page.ok_or(TextureAtlasBuilderError::NotEnoughSpace)?;
+        pages.push(page);
+        remaining = &remaining[len..];
+    }
+
+    Ok(pages)
+}
+
+/// Packs all images like [`build_texture_atlas`]. If rotation is allowed, tall images are also tried rotated
+/// by 90 degrees clockwise and the smaller of both atlases is used. Returns whether each image was rotated.
+fn build_rotated_texture_atlas(
+    configuration: &TitanConfiguration,
+    images: &[Image],
+) -> Result<(TextureAtlasLayout, Image, Vec<bool>), TextureAtlasBuilderError> {
+    let rotations = images
+        .iter()
+        .map(|image| configuration.allow_rotation && image.height() > image.width())
+        .collect::<Vec<_>>();
+    if !rotations.contains(&true) {
+        return build_texture_atlas(configuration, images)
+            .map(|(layout, texture)| (layout, texture, rotations));
+    }
+
+    let images_len = images.len();
+    let rotated_images = images
+        .iter()
+        .zip(&rotations)
+        .map(|(image, &rotated)| {
+            if rotated {
+                rotate_clockwise(image)
+            } else {
+                image.clone()
+            }
+        })
+        .collect::<Vec<_>>();
+    let upright = build_texture_atlas(configuration, images);
+    let rotated = build_texture_atlas(configuration, &rotated_images);
+
+    match (upright, rotated) {
+        (Ok((layout, texture)), Ok((rotated_layout, _)))
+            if layout.size.element_product() <= rotated_layout.size.element_product() =>
+        {
+            Ok((layout, texture, vec![false; images_len]))
+        }
+        (_, Ok((rotated_layout, rotated_texture))) => {
+            Ok((rotated_layout, rotated_texture, rotations))
+        }
+        (Ok((layout, texture)), Err(_)) => Ok((layout, texture, vec![false; images_len])),
+        (Err(error), Err(_)) => Err(error),
     }
 }
 
-#[cfg(test)]
-mod tests {
-    /* TODO: Tests */
+/// Everything that is produced from a titan manifest and its decoded source images.
+pub(crate) struct PackedAtlas {
+    pub(crate) layout: TextureAtlasLayout,
+    pub(crate) texture: Image,
+    pub(crate) additional_pages: Vec<Image>,
+    pub(crate) page_indices: Vec<usize>,
+    pub(crate) names: HashMap<String, usize>,
+    pub(crate) trims: Vec<SpriteTrim>,
+    pub(crate) animation: Vec<AnimationFrame>,
+    pub(crate) animations: HashMap<String, AnimationClip>,
+    pub(crate) rotations: Vec<bool>,
+    pub(crate) anchors: Vec<Vec2>,
+    pub(crate) slices: Vec<Option<BorderRect>>,
+    pub(crate) tileables: Vec<bool>,
+    pub(crate) source_rects: Vec<SpriteSource>,
+    pub(crate) sources: HashMap<String, Vec<Range<usize>>>,
+    pub(crate) groups: Vec<SpriteGroup>,
+    pub(crate) version: Option<String>,
+    pub(crate) checksum: u64,
+    pub(crate) default_index: Option<usize>,
+}
+
+impl PackedAtlas {
+    /// Frees the pixel data of every page, the layout and all metadata stay intact.
+    pub(crate) fn drop_pixels(&mut self) {
+        for page in std::iter::once(&mut self.texture).chain(&mut self.additional_pages) {
+            page.data = Vec::new();
+        }
+    }
+
+    /// Area covered by sprites compared to the area of all pages.
+    pub(crate) fn stats(&self) -> TextureAtlasStats {
+        /* Rects are only shared on the same page. Every layer of an array texture has the same rect,
+         * but a sprite of its own. */
+        let layered = self.texture.texture_descriptor.size.depth_or_array_layers > 1;
+        let used_area = self
+            .layout
+            .textures
+            .iter()
+            .zip(&self.page_indices)
+            .enumerate()
+            .map(|(index, (rect, &page_index))| (layered.then_some(index), page_index, *rect))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|(_, _, rect)| u64::from(rect.width()) * u64::from(rect.height()))
+            .sum::<u64>();
+        let atlas_area = std::iter::once(&self.texture)
+            .chain(&self.additional_pages)
+            .map(|page| {
+                let size = page.texture_descriptor.size;
+                u64::from(size.width)
+                    * u64::from(size.height)
+                    * u64::from(size.depth_or_array_layers)
+            })
+            .sum::<u64>();
+
+        TextureAtlasStats {
+            used_area,
+            atlas_area,
+            page_count: 1 + self.additional_pages.len(),
+            efficiency: used_area as f32 / atlas_area as f32,
+        }
+    }
+
+    /// Rect of every sprite divided by the size of its page.
+    pub(crate) fn uvs(&self) -> TextureAtlasUvs {
+        let pages = std::iter::once(&self.texture)
+            .chain(&self.additional_pages)
+            .collect::<Vec<_>>();
+        let uvs = self
+            .layout
+            .textures
+            .iter()
+            .zip(&self.page_indices)
+            .map(|(rect, &page_index)| {
+                let size = pages[page_index].size().as_vec2();
+                Rect::from_corners(rect.min.as_vec2() / size, rect.max.as_vec2() / size)
+            })
+            .collect();
+
+        TextureAtlasUvs(uvs)
+    }
+}
+
+/// Labels of the combined texture atlas and the texture atlas layout.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AtlasLabels<'a> {
+    pub(crate) texture: &'a str,
+    pub(crate) layout: &'a str,
+}
+
+impl Default for AtlasLabels<'_> {
+    fn default() -> Self {
+        Self {
+            texture: TEXTURE_LABEL,
+            layout: LAYOUT_LABEL,
+        }
+    }
+}
+
+/// Adds the texture, the layout and all metadata as labeled assets.
+pub(crate) fn add_labeled_assets(
+    load_context: &mut LoadContext<'_>,
+    packed_atlas: PackedAtlas,
+    labels: AtlasLabels<'_>,
+) -> TextureAtlas {
+    let stats = packed_atlas.stats();
+    debug!(
+        "{}: packed {} of {} pixels into {} pages, {:.1}% efficiency",
+        load_context.path().display(),
+        stats.used_area,
+        stats.atlas_area,
+        stats.page_count,
+        stats.efficiency * 100.0
+    );
+    let uvs = packed_atlas.uvs();
+    let PackedAtlas {
+        layout: texture_atlas_layout,
+        texture: atlas_texture,
+        additional_pages,
+        page_indices,
+        names,
+        trims,
+        animation,
+        animations,
+        rotations,
+        anchors,
+        slices,
+        tileables,
+        source_rects,
+        sources,
+        groups,
+        version,
+        checksum,
+        default_index,
+    } = packed_atlas;
+
+    let frame_count = texture_atlas_layout.len();
+    let atlas_size = atlas_texture.size();
+    let atlas_texture_handle =
+        load_context.add_loaded_labeled_asset(labels.texture.to_string(), atlas_texture.into());
+    let mut pages = vec![atlas_texture_handle.clone()];
+    for (page_index, page) in additional_pages.into_iter().enumerate() {
+        pages.push(load_context.add_loaded_labeled_asset(
+            format!("{}{}", labels.texture, page_index + 1),
+            page.into(),
+        ));
+    }
+    let texture_atlas_layout_handle = load_context
+        .add_loaded_labeled_asset(labels.layout.to_string(), texture_atlas_layout.into());
+    let names_handle =
+        load_context.add_loaded_labeled_asset("names", TextureAtlasNames(names).into());
+    load_context.add_loaded_labeled_asset("trim", TextureAtlasTrims(trims).into());
+    load_context.add_loaded_labeled_asset("animation", TextureAtlasAnimation(animation).into());
+    let animations_handle = load_context
+        .add_loaded_labeled_asset("animations", TextureAtlasAnimations(animations).into());
+    load_context.add_loaded_labeled_asset("rotation", TextureAtlasRotations(rotations).into());
+    load_context.add_loaded_labeled_asset("anchors", TextureAtlasAnchors(anchors).into());
+    load_context.add_loaded_labeled_asset("slices", TextureAtlasSlices(slices).into());
+    load_context.add_loaded_labeled_asset("tileable", TextureAtlasTileables(tileables).into());
+    load_context
+        .add_loaded_labeled_asset("source_rects", TextureAtlasSourceRects(source_rects).into());
+    load_context.add_loaded_labeled_asset("sources", TextureAtlasSources(sources).into());
+    load_context.add_loaded_labeled_asset("groups", TextureAtlasGroups(groups).into());
+    load_context.add_loaded_labeled_asset("stats", stats.into());
+    load_context.add_loaded_labeled_asset("uvs", uvs.into());
+
+    TextureAtlas {
+        texture: atlas_texture_handle,
+        layout: texture_atlas_layout_handle,
+        frame_count,
+        atlas_size,
+        names: names_handle,
+        animations: animations_handle,
+        pages,
+        page_indices,
+        version,
+        checksum,
+        default_index,
+    }
+}
+
+/// Rejects manifests that can never be packed, before any image is read.
+pub(crate) fn validate(titan: &Titan) -> Result<(), SpriteSheetLoaderError> {
+    validate_sizes(&titan.configuration)?;
+    if titan.textures.is_empty() && !titan.configuration.allow_empty {
+        return Err(SpriteSheetLoaderError::NoEntriesError);
+    }
+    if titan.configuration.alignment == 0 {
+        return Err(SpriteSheetLoaderError::ZeroAlignmentError);
+    }
+    if !titan.configuration.repack && titan.textures.len() > 1 {
+        return Err(SpriteSheetLoaderError::RepackError(titan.textures.len()));
+    }
+    if !titan.configuration.repack && titan.configuration.output == TitanOutput::Array {
+        return Err(SpriteSheetLoaderError::ArrayRepackError);
+    }
+    for (index, titan_entry) in titan.textures.iter().enumerate() {
+        if titan_entry.from_atlas.is_some() {
+            return Err(SpriteSheetLoaderError::UnresolvedAtlasError(index));
+        }
+        if titan_entry.glob.is_some() {
+            return Err(SpriteSheetLoaderError::UnresolvedGlobError(index));
+        }
+        if titan_entry.path.is_empty() == titan_entry.data.is_none() {
+            return Err(SpriteSheetLoaderError::EntrySourceError(index));
+        }
+        if let Some(source_rect) = titan_entry.source_rect {
+            if source_rect.is_empty() {
+                return Err(InvalidRectError(
+                    source_rect.min,
+                    source_rect.max,
+                    titan_entry.path.clone(),
+                    index,
+                )
+                .into());
+            }
+        }
+        if let TitanSpriteSheet::HeterogeneousSubpixel { rects, offset } = &titan_entry.sprite_sheet
+        {
+            for rect in rects {
+                let position = rect.position + *offset;
+                if !(position.is_finite()
+                    && rect.size.is_finite()
+                    && position.cmpge(Vec2::ZERO).all()
+                    && rect.size.cmpgt(Vec2::ZERO).all())
+                {
+                    return Err(SpriteSheetLoaderError::InvalidSubpixelRectError(
+                        position, rect.size, index,
+                    ));
+                }
+            }
+        }
+        if let TitanSpriteSheet::Homogeneous { columns, rows, .. } = titan_entry.sprite_sheet {
+            if columns == 0 || rows == 0 {
+                return Err(SpriteSheetLoaderError::EmptyGridError(columns, rows, index));
+            }
+        }
+        if let Some(scale) = titan_entry.scale {
+            if !(scale.is_finite() && scale > 0.0) {
+                return Err(SpriteSheetLoaderError::InvalidScaleError(scale, index));
+            }
+        }
+        let format = titan.configuration.format;
+        match titan_entry.color_space {
+            Some(color_space) if color_space != TitanColorSpace::of(format) => {
+                return Err(SpriteSheetLoaderError::ColorSpaceMismatch {
+                    index,
+                    color_space,
+                    format,
+                });
+            }
+            _ => {}
+        }
+    }
+    if titan.configuration.warn_on_overlap {
+        for ((index, rect_index), (other_index, other_rect_index)) in
+            overlapping_rects(&titan.textures)
+        {
+            warn!(
+                "Rect {} of entry {} overlaps rect {} of entry {} in image {}",
+                rect_index, index, other_rect_index, other_index, titan.textures[index].path
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds every pair of overlapping rects of heterogeneous sprite sheets within the same image, see `warn_on_overlap`.
+///
+/// Rects are identified by the index of their entry and their index within the sprite sheet.
+/// Only entries that share the image, `source_rect` and `rotate` are compared, since their rects have the same origin.
+fn overlapping_rects(titan_entries: &[TitanEntry]) -> Vec<((usize, usize), (usize, usize))> {
+    let mut images = HashMap::<_, Vec<_>>::new();
+    for (index, titan_entry) in titan_entries.iter().enumerate() {
+        if let TitanSpriteSheet::Heterogeneous { rects, offset } = &titan_entry.sprite_sheet {
+            /* Inline images are never shared */
+            let image = (
+                titan_entry.data.is_some().then_some(index),
+                titan_entry.path.as_str(),
+                titan_entry.source_rect,
+                titan_entry.rotate,
+            );
+            images
+                .entry(image)
+                .or_default()
+                .extend(rects.iter().enumerate().map(|(rect_index, rect)| {
+                    let min = rect.position + *offset;
+                    (
+                        (index, rect_index),
+                        URect::from_corners(min, min + rect.size),
+                    )
+                }));
+        }
+    }
+
+    let mut overlaps = Vec::new();
+    for mut rects in images.into_values() {
+        /* Sweep from left to right, only rects that start before the current one ends can overlap */
+        rects.sort_by_key(|(id, rect)| (rect.min.x, *id));
+        for (position, (id, rect)) in rects.iter().enumerate() {
+            for (other_id, other_rect) in rects[position + 1..]
+                .iter()
+                .take_while(|(_, other_rect)| other_rect.min.x < rect.max.x)
+            {
+                if !rect.intersect(*other_rect).is_empty() {
+                    overlaps.push((*id.min(other_id), *id.max(other_id)));
+                }
+            }
+        }
+    }
+    overlaps.sort_unstable();
+
+    overlaps
+}
+
+/// Packs `titan` without the asset system, e.g. for tests or command line tools.
+///
+/// `resolve` is called once for every distinct image path of the manifest, with `base_path` prepended,
+/// and has to return the decoded image.
+/// Entries with inline data are decoded from the manifest. All metadata is dropped, only the layout and the
+/// combined texture atlas are returned. `allow_multiple_pages` is ignored, all sprites have to fit into a single page.
+/// `from_atlas` and `glob` entries are not supported.
+///
+/// ```
+/// # use bevy::{image::Image, math::UVec2};
+/// # use bevy_titan::{asset_loader::build_atlas, manifest::Titan};
+/// let titan = ron::de::from_str::<Titan>("(textures: [(path: \"a.png\"), (path: \"b.png\")])").unwrap();
+/// let (layout, texture) = build_atlas(titan, |_path| Image::default()).unwrap();
+/// assert_eq!(layout.len(), 2);
+/// assert_eq!(layout.size, texture.size());
+/// ```
+pub fn build_atlas(
+    mut titan: Titan,
+    resolve: impl FnMut(&str) -> Image,
+) -> Result<(TextureAtlasLayout, Image), SpriteSheetLoaderError> {
+    apply_base_path(&mut titan);
+    validate(&titan)?;
+
+    pack_single_page(titan, resolve)
+}
+
+/// Packs the already validated `titan` into a single page, like [`build_atlas`].
+fn pack_single_page(
+    mut titan: Titan,
+    mut resolve: impl FnMut(&str) -> Image,
+) -> Result<(TextureAtlasLayout, Image), SpriteSheetLoaderError> {
+    titan.configuration.allow_multiple_pages = false;
+    let packed_atlas = pack_titan(titan, |path| Ok(resolve(path)))?;
+
+    Ok((packed_atlas.layout, packed_atlas.texture))
+}
+
+/// Parses the titan ron file `ron` and packs it synchronously with the decoded `images`, keyed by their path,
+/// e.g. to assert the result of a titan ron file in a test without an asset server.
+///
+/// Like [`build_atlas`], only the layout and the combined texture atlas are returned. `extends` is not resolved,
+/// `from_atlas` and `glob` entries are not supported. An image that is not in `images` fails like a missing file,
+/// or its entries are skipped with `ignore_missing`.
+///
+/// ```
+/// # use bevy::{image::Image, math::UVec2, utils::HashMap};
+/// # use bevy_titan::asset_loader::load_titan_from_str;
+/// let images = HashMap::from([("a.png".to_string(), Image::default())]);
+/// let (layout, texture) = load_titan_from_str("(textures: [(path: \"a.png\")])", &images).unwrap();
+/// assert_eq!(layout.len(), 1);
+/// assert!(load_titan_from_str("(textures: [(path: \"b.png\")])", &images).is_err());
+/// ```
+pub fn load_titan_from_str(
+    ron: &str,
+    images: &HashMap<String, Image>,
+) -> Result<(TextureAtlasLayout, Image), SpriteSheetLoaderError> {
+    let mut titan = TitanSyntax::Ron.deserialize::<Titan>(ron.as_bytes())?;
+    /* Resolve the paths like build_atlas does, which does not prepend the cleared base path again */
+    apply_base_path(&mut titan);
+    validate(&titan)?;
+    let missing = source_paths(&titan.textures)
+        .into_iter()
+        .filter(|path| !images.contains_key(*path))
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    match missing.first() {
+        Some(_) if titan.configuration.ignore_missing => skip_missing(&mut titan, &missing)?,
+        Some(path) => {
+            return Err(ReadAssetBytesError::from(AssetReaderError::NotFound(path.into())).into())
+        }
+        None => {}
+    }
+
+    pack_single_page(titan, |path| images[path].clone())
+}
+
+/// Decodes the inline images of the already [validated](validate) `titan`, resolves all other images and packs them.
+///
+/// This is the core of the [`SpriteSheetLoader`], which resolves images by reading them through the asset system.
+pub(crate) fn pack_titan(
+    mut titan: Titan,
+    mut resolve: impl FnMut(&str) -> Result<Image, SpriteSheetLoaderError>,
+) -> Result<PackedAtlas, SpriteSheetLoaderError> {
+    let mut source_images = decode_inline_images(&mut titan.textures)?;
+    for path in source_paths(&titan.textures) {
+        source_images.insert(path.to_string(), resolve(path)?);
+    }
+
+    pack_atlas(titan, source_images)
+}
+
+/// Removes all entries whose tags are not in `active_tags`. Untagged entries are kept.
+pub(crate) fn filter_tags(titan: &mut Titan, active_tags: &[String]) {
+    titan.textures.retain(|titan_entry| {
+        titan_entry.tags.is_empty() || titan_entry.tags.iter().any(|tag| active_tags.contains(tag))
+    });
+}
+
+/// Prepends `base_path` to the path or glob of every entry that reads image assets and clears it,
+/// so that applying it twice has no effect.
+///
+/// Entries with inline data, images of a [`TitanBuilder`](crate::builder::TitanBuilder) and `from_atlas` entries,
+/// whose frames are read from the paths of the other texture atlas, are left as they are.
+pub(crate) fn apply_base_path(titan: &mut Titan) {
+    let base_path = std::mem::take(&mut titan.configuration.base_path);
+    if base_path.is_empty() {
+        return;
+    }
+
+    let prepend = |path: &mut String| {
+        *path = Path::new(&base_path)
+            .join(&*path)
+            .to_string_lossy()
+            .into_owned();
+    };
+    for titan_entry in &mut titan.textures {
+        if let Some(glob) = &mut titan_entry.glob {
+            prepend(glob);
+        } else if titan_entry.data.is_none()
+            && titan_entry.from_atlas.is_none()
+            && !titan_entry.path.starts_with('<')
+        {
+            prepend(&mut titan_entry.path);
+        }
+    }
+}
+
+/// Removes all entries of the `missing` images, see `ignore_missing`.
+pub(crate) fn skip_missing(
+    titan: &mut Titan,
+    missing: &[String],
+) -> Result<(), SpriteSheetLoaderError> {
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    for path in missing {
+        warn!(
+            "Skipping all entries of image {}, because it does not exist",
+            path
+        );
+    }
+    titan
+        .textures
+        .retain(|titan_entry| titan_entry.data.is_some() || !missing.contains(&titan_entry.path));
+    if titan.textures.is_empty() && !titan.configuration.allow_empty {
+        return Err(SpriteSheetLoaderError::NoEntriesError);
+    }
+
+    Ok(())
+}
+
+/// Extracts, processes and packs all sprites of a validated manifest.
+///
+/// `source_images` has to contain the decoded image for every path of the manifest.
+/// The last entry that uses an image takes it instead of copying it and drops it as soon as it is sliced.
+///
+/// All sprites are extracted before the first one is packed, because the [`TextureAtlasBuilder`] has to know
+/// the sizes of all sprites to place any of them. Peak memory therefore holds every extracted sprite and the
+/// source images still used by later entries. Order entries by image to drop each one early.
+pub(crate) fn pack_atlas(
+    titan: Titan,
+    mut source_images: HashMap<String, Image>,
+) -> Result<PackedAtlas, SpriteSheetLoaderError> {
+    let configuration = titan.configuration;
+    let titan_entries = titan.textures;
+    let version = titan.version;
+    if titan_entries.is_empty() {
+        return Ok(empty_atlas(&configuration, version));
+    }
+
+    /* Check the limits before anything is allocated */
+    let (images_len, images_area) =
+        titan_entries
+            .iter()
+            .fold((0u64, 0u64), |(len, area), titan_entry| {
+                let size = titan_entry
+                    .rotate
+                    .rotated_size(titan_entry.source_rect.map_or_else(
+                        || source_images[titan_entry.path.as_str()].size(),
+                        |source_rect| source_rect.size(),
+                    ));
+                let (entry_len, entry_area) =
+                    sprite_sheet_extent(&titan_entry.sprite_sheet, size, configuration.clamp_rects);
+                /* Flipped copies count as well */
+                let copies = if titan_entry.flip.is_some() { 2 } else { 1 };
+                let (entry_len, entry_area) =
+                    (entry_len * copies, entry_area.saturating_mul(copies));
+                (
+                    len.saturating_add(entry_len),
+                    area.saturating_add(entry_area),
+                )
+            });
+    if images_len > configuration.max_frames {
+        return Err(SpriteSheetLoaderError::LimitExceeded(
+            "Number of sprites",
+            images_len,
+            configuration.max_frames,
+        ));
+    }
+    if images_area > configuration.max_total_area {
+        return Err(SpriteSheetLoaderError::LimitExceeded(
+            "Total area of all sprites",
+            images_area,
+            configuration.max_total_area,
+        ));
+    }
+    let images_len = images_len as usize;
+
+    let mut last_uses = vec![false; titan_entries.len()];
+    {
+        let mut used_paths = HashSet::new();
+        for (index, titan_entry) in titan_entries.iter().enumerate().rev() {
+            last_uses[index] = used_paths.insert(titan_entry.path.as_str());
+        }
+    }
+
+    /* A single image is passed through as is without always_pack and array layers are not limited */
+    let check_size =
+        configuration.output == TitanOutput::Atlas && (configuration.always_pack || images_len > 1);
+    let mut images = Vec::with_capacity(images_len);
+    let mut names = HashMap::new();
+    let mut trims = Vec::with_capacity(images_len);
+    let mut durations = Vec::with_capacity(images_len);
+    let mut anchors = Vec::with_capacity(images_len);
+    let mut slices = Vec::with_capacity(images_len);
+    let mut tileables = Vec::with_capacity(images_len);
+    let mut source_rects = Vec::with_capacity(images_len);
+    let mut sources = HashMap::new();
+    let mut groups = Vec::with_capacity(titan_entries.len());
+    let mut laid_out_atlas = None;
+    for (index, mut titan_entry) in titan_entries.into_iter().enumerate() {
+        /* Get and insert all rects */
+        let name = titan_entry.name.take();
+        let group_name = name.clone();
+        let name_prefix = titan_entry.name_prefix.take();
+        let mut sprite_sheet = std::mem::take(&mut titan_entry.sprite_sheet);
+        let single = matches!(sprite_sheet, TitanSpriteSheet::None);
+        let (rect_names, rect_anchors): (Vec<_>, Vec<_>) = match &mut sprite_sheet {
+            TitanSpriteSheet::Heterogeneous { rects, .. } => rects
+                .iter_mut()
+                .map(|rect| (rect.name.take(), rect.anchor))
+                .unzip(),
+            TitanSpriteSheet::HeterogeneousSubpixel { rects, .. } => rects
+                .iter_mut()
+                .map(|rect| (rect.name.take(), rect.anchor))
+                .unzip(),
+            _ => Default::default(),
+        };
+        let anchor = titan_entry.anchor.unwrap_or(DEFAULT_ANCHOR);
+        let nine_slice = titan_entry.nine_slice;
+        let trim_entry = titan_entry.trim.unwrap_or(configuration.trim) && !titan_entry.tileable;
+        let color_key = titan_entry.color_key.or(configuration.color_key);
+        let auto_format_conversion = titan_entry
+            .auto_format_conversion
+            .unwrap_or(configuration.auto_format_conversion);
+        let format = titan_entry.format;
+        let channel_mapping = titan_entry.channel_mapping;
+        let color_space = titan_entry.color_space;
+        let scale_factor = titan_entry.scale;
+        let scale_filter = titan_entry.scale_filter;
+        let duration = titan_entry
+            .duration_ms
+            .map(|ms| Duration::from_millis(ms.into()));
+        let titan_entry_path = titan_entry.path.as_str();
+        let image = if last_uses[index] {
+            Cow::Owned(
+                source_images
+                    .remove(titan_entry_path)
+                    .expect("Every path of the manifest has a decoded image"),
+            )
+        } else {
+            Cow::Borrowed(&source_images[titan_entry_path])
+        };
+        /* Crop to the source rect, all rects of the sprite sheet are relative to it */
+        let source_rect = match titan_entry.source_rect {
+            Some(rect) if configuration.coordinate_origin == TitanCoordinateOrigin::BottomLeft => {
+                Some(rect_to_top_left(rect, image.height()).ok_or_else(|| {
+                    InvalidRectError(rect.min, rect.max, titan_entry_path.to_string(), index)
+                })?)
+            }
+            source_rect => source_rect,
+        };
+        let source_offset = source_rect.map_or(UVec2::ZERO, |rect| rect.min);
+        let image = match source_rect {
+            Some(rect) => Cow::Owned(extract_texture_from_rect(&image, rect).ok_or_else(|| {
+                InvalidRectError(rect.min, rect.max, titan_entry_path.to_string(), index)
+            })?),
+            None => image,
+        };
+        /* Correct the orientation, all rects of the sprite sheet are relative to the rotated image */
+        let rotation = titan_entry.rotate;
+        let unrotated_size = image.size();
+        let image = match rotation {
+            TitanRotation::None => image,
+            TitanRotation::Cw90 => Cow::Owned(rotate_clockwise(&image)),
+            TitanRotation::Cw180 => Cow::Owned(flip_image(&image, true, true)),
+            TitanRotation::Cw270 => Cow::Owned(rotate_counterclockwise(&image)),
+        };
+        let first_index = trims.len();
+        let process = |image: &mut Image| {
+            process_image(
+                image,
+                format.map(|format| (format, channel_mapping)),
+                color_space,
+                color_key,
+                auto_format_conversion,
+                &configuration,
+                titan_entry_path,
+            )
+        };
+        let rects = if configuration.repack {
+            let rects = push_textures(
+                &mut images,
+                sprite_sheet,
+                titan_entry_path,
+                index,
+                image,
+                configuration.clamp_rects,
+                configuration.coordinate_origin,
+            )?;
+
+            /* Scale, convert, color key, snap alpha, premultiply and trim all inserted rects */
+            for image in &mut images[first_index..] {
+                if let Some(scale_factor) = scale_factor {
+                    let size = (image.size().as_vec2() * scale_factor)
+                        .round()
+                        .as_uvec2()
+                        .max(UVec2::ONE);
+                    *image = scale(image, size, scale_filter).ok_or_else(|| {
+                        SpriteSheetLoaderError::ScaleError(
+                            titan_entry_path.to_string(),
+                            image.texture_descriptor.format,
+                            scale_filter,
+                        )
+                    })?;
+                }
+                process(image)?;
+                trims.push(trim_image(image, trim_entry, titan_entry_path));
+                if check_size && !fits_max_size(&configuration, image.size()) {
+                    return Err(SpriteSheetLoaderError::SourceTooLarge {
+                        path: titan_entry_path.to_string(),
+                        size: image.size(),
+                        max_size: configuration.max_size,
+                    });
+                }
+            }
+
+            rects
+        } else {
+            /* Process the whole image once and keep the rects as they are */
+            let mut image = image.into_owned();
+            let mut rects = sprite_rects(
+                &sprite_sheet,
+                &image,
+                titan_entry_path,
+                index,
+                configuration.clamp_rects,
+            )?;
+            if configuration.coordinate_origin == TitanCoordinateOrigin::BottomLeft {
+                rects_to_top_left(&sprite_sheet, &mut rects, image.height());
+            }
+            process(&mut image)?;
+            trims.extend(rects.iter().map(|rect| SpriteTrim {
+                original_size: rect.size(),
+                offset: UVec2::ZERO,
+            }));
+            laid_out_atlas = Some((rects.clone(), image, titan_entry_path.to_string()));
+
+            rects
+        };
+        let transformed = rotation != TitanRotation::None
+            || scale_factor.is_some()
+            || format.is_some()
+            || color_space.is_some()
+            || color_key.is_some();
+        source_rects.extend(rects.into_iter().enumerate().map(|(i, rect)| {
+            let index = first_index + i;
+            let trimmed =
+                configuration.repack && images[index].size() != trims[index].original_size;
+            let rect = rotation.unrotate_rect(rect, unrotated_size);
+            SpriteSource {
+                path: titan_entry_path.to_string(),
+                rect: URect::from_corners(rect.min + source_offset, rect.max + source_offset),
+                transformed: transformed || trimmed,
+            }
+        }));
+
+        /* Time all inserted rects */
+        durations.resize(trims.len(), duration);
+
+        /* Slice all inserted rects */
+        if let Some(nine_slice) = nine_slice {
+            if let Some(trim) = trims[first_index..]
+                .iter()
+                .find(|trim| !nine_slice.fits(trim.original_size))
+            {
+                return Err(InvalidNineSliceError(
+                    titan_entry_path.to_string(),
+                    index,
+                    trim.original_size,
+                )
+                .into());
+            }
+        }
+        slices.resize(trims.len(), nine_slice.map(BorderRect::from));
+        tileables.resize(trims.len(), titan_entry.tileable);
+
+        /* Anchor all inserted rects */
+        anchors.resize(trims.len(), anchor);
+        for (i, rect_anchor) in rect_anchors.into_iter().enumerate() {
+            if let Some(rect_anchor) = rect_anchor {
+                anchors[first_index + i] = rect_anchor;
+            }
+        }
+
+        /* Name all inserted rects */
+        if let Some(name) = &name {
+            insert_names(&mut names, name.clone(), first_index..trims.len(), single)?;
+        }
+        if let Some(name_prefix) = &name_prefix {
+            insert_prefixed_names(&mut names, name_prefix, "", first_index..trims.len())?;
+        }
+        for (i, rect_name) in rect_names.iter().enumerate() {
+            if let Some(rect_name) = rect_name {
+                let index = first_index + i;
+                insert_names(&mut names, rect_name.clone(), index..index + 1, true)?;
+            }
+        }
+
+        /* Append a flipped copy of all inserted rects */
+        if let Some(flip) = titan_entry.flip.filter(|_| configuration.repack) {
+            let flipped_index = trims.len();
+            for index in first_index..flipped_index {
+                let image = flip_image(&images[index], flip.x, flip.y);
+                let trim = trims[index];
+                let trimmed_offset = trim.original_size - image.size() - trim.offset;
+                trims.push(SpriteTrim {
+                    original_size: trim.original_size,
+                    offset: UVec2::new(
+                        if flip.x {
+                            trimmed_offset.x
+                        } else {
+                            trim.offset.x
+                        },
+                        if flip.y {
+                            trimmed_offset.y
+                        } else {
+                            trim.offset.y
+                        },
+                    ),
+                });
+                images.push(image);
+                durations.push(None);
+                let anchor = anchors[index];
+                anchors.push(Vec2::new(
+                    if flip.x { 1.0 - anchor.x } else { anchor.x },
+                    if flip.y { 1.0 - anchor.y } else { anchor.y },
+                ));
+                slices.push(slices[index].map(|slice: BorderRect| BorderRect {
+                    left: if flip.x { slice.right } else { slice.left },
+                    right: if flip.x { slice.left } else { slice.right },
+                    top: if flip.y { slice.bottom } else { slice.top },
+                    bottom: if flip.y { slice.top } else { slice.bottom },
+                }));
+                tileables.push(titan_entry.tileable);
+                source_rects.push(SpriteSource {
+                    transformed: true,
+                    ..source_rects[index].clone()
+                });
+            }
+
+            if let Some(name) = name {
+                let name = format!("{name}{}", flip.suffix);
+                insert_names(&mut names, name, flipped_index..trims.len(), single)?;
+            }
+            if let Some(name_prefix) = &name_prefix {
+                insert_prefixed_names(
+                    &mut names,
+                    name_prefix,
+                    &flip.suffix,
+                    flipped_index..trims.len(),
+                )?;
+            }
+            for (i, rect_name) in rect_names.into_iter().enumerate() {
+                if let Some(rect_name) = rect_name {
+                    let index = flipped_index + i;
+                    let name = format!("{rect_name}{}", flip.suffix);
+                    insert_names(&mut names, name, index..index + 1, true)?;
+                }
+            }
+        }
+
+        /* Remember the indices produced by the image, including flipped copies */
+        if titan_entry.data.is_none() {
+            let ranges: &mut Vec<Range<usize>> =
+                sources.entry(titan_entry_path.to_string()).or_default();
+            match ranges.last_mut() {
+                Some(range) if range.end == first_index => range.end = trims.len(),
+                _ => ranges.push(first_index..trims.len()),
+            }
+        }
+        groups.push(SpriteGroup {
+            name: group_name,
+            indices: first_index..trims.len(),
+        });
+    }
+
+    let animation = durations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, duration)| duration.map(|duration| AnimationFrame { index, duration }))
+        .collect();
+    let animations = build_animations(titan.animations, &durations, &names)?;
+
+    /* Remember what did not fit for a more helpful error */
+    let images_count = images.len();
+    let images_area = images
+        .iter()
+        .map(|image| u64::from(image.width()) * u64::from(image.height()))
+        .sum();
+    /* Only packed sprites have padding of their own, laid out ones may be surrounded by other sprites */
+    let extrude = configuration.extrude_edges
+        && laid_out_atlas.is_none()
+        && configuration.output == TitanOutput::Atlas;
+    let (mut layout, mut pages, page_indices, rotations) = match laid_out_atlas {
+        Some((rects, texture, path)) => {
+            let (layout, texture, rotations) =
+                lay_out_texture_atlas(&configuration, rects, texture, path)?;
+            (layout, vec![texture], vec![0; images_count], rotations)
+        }
+        None if configuration.output == TitanOutput::Array => {
+            let (layout, texture) = build_array_texture(&configuration, images)?;
+            (
+                layout,
+                vec![texture],
+                vec![0; images_count],
+                vec![false; images_count],
+            )
+        }
+        None => {
+            let order = packing_order(configuration.sort_order, &images, &names);
+            let mut images = images.into_iter().map(Some).collect::<Vec<_>>();
+            let sorted_images = order
+                .iter()
+                .filter_map(|&index| images[index].take())
+                .collect::<Vec<_>>();
+            let sorted_pages =
+                build_pages(&configuration, &sorted_images).map_err(|error| match error {
+                    TextureAtlasBuilderError::NotEnoughSpace => SpriteSheetLoaderError::from(
+                        NotEnoughSpaceError(images_count, images_area, configuration.max_size),
+                    ),
+                    error => error.into(),
+                })?;
+
+            /* Merge all pages into a single layout in the order of declaration */
+            let mut layout = TextureAtlasLayout::new_empty(
+                sorted_pages
+                    .iter()
+                    .fold(UVec2::ZERO, |size, (page_layout, _, _)| {
+                        size.max(page_layout.size)
+                    }),
+            );
+            layout.textures = vec![URect::default(); images_count];
+            let mut page_indices = vec![0; images_count];
+            let mut rotations = vec![false; images_count];
+            let mut pages = Vec::with_capacity(sorted_pages.len());
+            let mut sorted_indices = order.iter();
+            for (page_index, (page_layout, texture, page_rotations)) in
+                sorted_pages.into_iter().enumerate()
+            {
+                for (&rect, rotated) in page_layout.textures.iter().zip(page_rotations) {
+                    let &index = sorted_indices
+                        .next()
+                        .expect("Every sprite is packed exactly once");
+                    layout.textures[index] = rect;
+                    page_indices[index] = page_index;
+                    rotations[index] = rotated;
+                }
+                pages.push(texture);
+            }
+            (layout, pages, page_indices, rotations)
+        }
+    };
+    if extrude {
+        for ((&rect, &tileable), &page_index) in
+            layout.textures.iter().zip(&tileables).zip(&page_indices)
+        {
+            extrude_edges(
+                &mut pages[page_index],
+                rect,
+                configuration.padding,
+                tileable,
+            );
+        }
+    }
+    if configuration.power_of_two && configuration.output == TitanOutput::Atlas {
+        /* Growing to the right and to the bottom keeps every rect where it is */
+        for texture in &mut pages {
+            let size = texture.size().map(u32::next_power_of_two);
+            if size != texture.size() {
+                *texture = pad_image(texture, size);
+            }
+        }
+        layout.size = layout.size.map(u32::next_power_of_two);
+    }
+    for texture in &mut pages {
+        if configuration.generate_mipmaps && !generate_mipmaps(texture) {
+            return Err(SpriteSheetLoaderError::MipmapFormatError(
+                texture.texture_descriptor.format,
+            ));
+        }
+        texture.sampler = configuration.sampler.clone().into();
+        texture.asset_usage = configuration.render_asset_usages;
+        texture.texture_descriptor.usage = configuration.texture_usages;
+    }
+    let texture = pages.remove(0);
+    if let Some(default_index) = configuration.default_index {
+        if default_index >= layout.len() {
+            return Err(SpriteSheetLoaderError::InvalidDefaultIndexError(
+                default_index,
+                layout.len(),
+            ));
+        }
+    }
+
+    Ok(PackedAtlas {
+        layout,
+        texture,
+        additional_pages: pages,
+        page_indices,
+        names,
+        trims,
+        animation,
+        animations,
+        rotations,
+        anchors,
+        slices,
+        tileables,
+        source_rects,
+        sources,
+        groups,
+        version,
+        checksum: 0,
+        default_index: configuration.default_index,
+    })
+}
+
+/// A texture atlas without any sprite, whose texture is a single transparent pixel or block, see `allow_empty`.
+fn empty_atlas(configuration: &TitanConfiguration, version: Option<String>) -> PackedAtlas {
+    /* A single block for block compressed formats, which Image::new can not compute the size of */
+    let block_size = configuration.format.block_copy_size(None).unwrap_or(4);
+    let (block_width, block_height) = configuration.format.block_dimensions();
+    let mut texture = Image {
+        data: vec![0; block_size as usize],
+        asset_usage: configuration.render_asset_usages,
+        ..Default::default()
+    };
+    texture.texture_descriptor.size = Extent3d {
+        width: block_width,
+        height: block_height,
+        depth_or_array_layers: 1,
+    };
+    texture.texture_descriptor.format = configuration.format;
+    texture.sampler = configuration.sampler.clone().into();
+    texture.texture_descriptor.usage = configuration.texture_usages;
+
+    PackedAtlas {
+        layout: TextureAtlasLayout::new_empty(texture.size()),
+        texture,
+        additional_pages: Vec::new(),
+        page_indices: Vec::new(),
+        names: HashMap::new(),
+        trims: Vec::new(),
+        animation: Vec::new(),
+        animations: HashMap::new(),
+        rotations: Vec::new(),
+        anchors: Vec::new(),
+        slices: Vec::new(),
+        tileables: Vec::new(),
+        source_rects: Vec::new(),
+        sources: HashMap::new(),
+        groups: Vec::new(),
+        version,
+        checksum: 0,
+        default_index: configuration.default_index,
+    }
+}
+
+/// Converts, color keys and premultiplies a single image in place.
+///
+/// A declared color space only reinterprets the pixels, e.g. a linear normal map that was decoded
+/// as sRGB keeps its bytes, but gets the linear texture format.
+fn process_image(
+    image: &mut Image,
+    format: Option<(TextureFormat, TitanChannelMapping)>,
+    color_space: Option<TitanColorSpace>,
+    color_key: Option<[u8; 3]>,
+    auto_format_conversion: bool,
+    configuration: &TitanConfiguration,
+    path: &str,
+) -> Result<(), SpriteSheetLoaderError> {
+    if let Some((format, channel_mapping)) = format {
+        *image = convert(image, format, channel_mapping).ok_or_else(|| {
+            SpriteSheetLoaderError::FormatConversionError(
+                path.to_string(),
+                image.texture_descriptor.format,
+                format,
+            )
+        })?;
+    }
+    if let Some(color_space) = color_space {
+        image.texture_descriptor.format = color_space.apply(image.texture_descriptor.format);
+    }
+    check_format(image, auto_format_conversion, configuration, path)?;
+    if let Some(color_key) = color_key {
+        if !apply_color_key(image, color_key, configuration.color_key_tolerance) {
+            warn!(
+                "Can not apply color key to image {} of format {:?}, because it has no unsigned 8 bit alpha channel",
+                path, image.texture_descriptor.format
+            );
+        }
+    }
+    /* Before premultiplying, which would otherwise keep the colors of the faint pixels */
+    if let Some(alpha_threshold) = configuration.alpha_threshold {
+        if !snap_alpha(image, alpha_threshold) {
+            warn!(
+                "Can not apply alpha threshold to image {} of format {:?}, because it has no unsigned 8 bit alpha channel",
+                path, image.texture_descriptor.format
+            );
+        }
+    }
+    if configuration.premultiply_alpha && !premultiply_alpha(image) {
+        warn!(
+            "Can not premultiply alpha of image {} of format {:?}, because it has no unsigned 8 bit alpha channel",
+            path, image.texture_descriptor.format
+        );
+    }
+
+    Ok(())
+}
+
+/// Uses `rects` as layout of `texture` without extracting or packing any sprite.
+/// Only the texture format of the combined texture atlas is applied.
+fn lay_out_texture_atlas(
+    configuration: &TitanConfiguration,
+    rects: Vec<URect>,
+    mut texture: Image,
+    path: String,
+) -> Result<(TextureAtlasLayout, Image, Vec<bool>), SpriteSheetLoaderError> {
+    let found = texture.texture_descriptor.format;
+    if found != configuration.format {
+        texture = configuration
+            .auto_format_conversion
+            .then(|| texture.convert(configuration.format))
+            .flatten()
+            .ok_or(SpriteSheetLoaderError::FormatConversionError(
+                path,
+                found,
+                configuration.format,
+            ))?;
+    }
+
+    let mut layout = TextureAtlasLayout::new_empty(texture.size());
+    for rect in &rects {
+        layout.add_texture(*rect);
+    }
+
+    Ok((layout, texture, vec![false; rects.len()]))
+}
+
+/// Applies `on_format_mismatch` to a sprite whose texture format differs from the combined texture atlas.
+/// The conversion itself is left to the [`TextureAtlasBuilder`], unless only `auto_format_conversion`
+/// of the entry enables it.
+fn check_format(
+    image: &mut Image,
+    auto_format_conversion: bool,
+    configuration: &TitanConfiguration,
+    path: &str,
+) -> Result<(), SpriteSheetLoaderError> {
+    let found = image.texture_descriptor.format;
+    if found == configuration.format {
+        return Ok(());
+    }
+
+    match configuration.on_format_mismatch {
+        TitanFormatMismatch::Convert => {}
+        TitanFormatMismatch::Error => {
+            return Err(SpriteSheetLoaderError::FormatMismatch {
+                expected: configuration.format,
+                found,
+                path: path.to_string(),
+            });
+        }
+        TitanFormatMismatch::WarnAndConvert => {
+            warn!(
+                "Image {} has texture format {:?}, but the combined texture atlas uses {:?}",
+                path, found, configuration.format
+            );
+        }
+    }
+    if !auto_format_conversion {
+        return Err(SpriteSheetLoaderError::FormatMismatch {
+            expected: configuration.format,
+            found,
+            path: path.to_string(),
+        });
+    }
+    if !configuration.auto_format_conversion {
+        *image = convert(image, configuration.format, TitanChannelMapping::default()).ok_or_else(
+            || {
+                SpriteSheetLoaderError::FormatConversionError(
+                    path.to_string(),
+                    found,
+                    configuration.format,
+                )
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+fn validate_sizes(configuration: &TitanConfiguration) -> Result<(), SpriteSheetLoaderError> {
+    let initial_size = configuration.initial_size;
+    let max_size = configuration.max_size;
+
+    if initial_size.cmpeq(UVec2::ZERO).any() || max_size.cmpeq(UVec2::ZERO).any() {
+        return Err(SpriteSheetLoaderError::ZeroSizeError(
+            initial_size,
+            max_size,
+        ));
+    }
+
+    if max_size.x < initial_size.x || max_size.y < initial_size.y {
+        return Err(SpriteSheetLoaderError::SizeMismatchError(
+            initial_size,
+            max_size,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Packs all images into a single atlas texture, aligned to `alignment`.
+///
+/// A single image is used as the atlas texture as is, unless `always_pack` is configured.
+pub(crate) fn build_texture_atlas(
+    configuration: &TitanConfiguration,
+    images: &[Image],
+) -> Result<(TextureAtlasLayout, Image), TextureAtlasBuilderError> {
+    let alignment = configuration.alignment;
+    if alignment <= 1 {
+        return build_unaligned_texture_atlas(configuration, images);
+    }
+
+    /* Grow every sprite, so that it takes up a multiple of the alignment including its padding.
+     * Every position the packer chooses is then a multiple of the alignment as well. */
+    let align = |size: UVec2| size.map(|length| length.next_multiple_of(alignment));
+    let padding = configuration.padding;
+    let sizes = images.iter().map(Image::size).collect::<Vec<_>>();
+    let images = images
+        .iter()
+        .map(|image| pad_image(image, align(image.size() + padding) - padding))
+        .collect::<Vec<_>>();
+    let (mut layout, texture) = build_unaligned_texture_atlas(configuration, &images)?;
+    for (rect, size) in layout.textures.iter_mut().zip(sizes) {
+        rect.max = rect.min + size;
+    }
+    let texture = pad_image(&texture, align(texture.size()));
+    layout.size = texture.size();
+
+    Ok((layout, texture))
+}
+
+fn build_unaligned_texture_atlas(
+    configuration: &TitanConfiguration,
+    images: &[Image],
+) -> Result<(TextureAtlasLayout, Image), TextureAtlasBuilderError> {
+    /* A single image is passed through as is, only converted if the format does not match */
+    if !configuration.always_pack && images.len() == 1 {
+        let image = if images[0].texture_descriptor.format == configuration.format {
+            Some(images[0].clone())
+        } else if configuration.auto_format_conversion {
+            images[0].convert(configuration.format)
+        } else {
+            None
+        };
+
+        if let Some(mut image) = image {
+            image.asset_usage = RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD;
+            let mut texture_atlas_layout = TextureAtlasLayout::new_empty(image.size());
+            texture_atlas_layout.add_texture(URect::from_corners(UVec2::ZERO, image.size()));
+
+            return Ok((texture_atlas_layout, image));
+        }
+    }
+
+    let mut texture_atlas_builder = TextureAtlasBuilder::default();
+    texture_atlas_builder
+        .initial_size(configuration.initial_size)
+        .max_size(configuration.max_size)
+        .format(configuration.format)
+        .auto_format_conversion(configuration.auto_format_conversion)
+        .padding(configuration.padding);
+
+    if configuration.deduplicate {
+        /* Only add the first occurrence of identical images and remember where each image went */
+        let mut unique_images = HashMap::new();
+        let mut unique_indices = Vec::with_capacity(images.len());
+        for image in images {
+            let key = (
+                image.size(),
+                image.texture_descriptor.format,
+                image.data.as_slice(),
+            );
+            let unique_images_len = unique_images.len();
+            let unique_index = *unique_images.entry(key).or_insert_with(|| {
+                texture_atlas_builder.add_texture(None, image);
+                unique_images_len
+            });
+            unique_indices.push(unique_index);
+        }
+        let (mut texture_atlas_layout, _, atlas_texture) = texture_atlas_builder.build()?;
+        texture_atlas_layout.textures = unique_indices
+            .into_iter()
+            .map(|unique_index| texture_atlas_layout.textures[unique_index])
+            .collect();
+
+        return Ok((texture_atlas_layout, atlas_texture));
+    }
+
+    for image in images {
+        texture_atlas_builder.add_texture(None, image);
+    }
+    let (texture_atlas_layout, _, atlas_texture) = texture_atlas_builder.build()?;
+
+    Ok((texture_atlas_layout, atlas_texture))
+}
+
+/// Whether a sprite of `size` fits into `max_size` on its own, including its padding and alignment,
+/// upright or rotated if rotation is allowed.
+fn fits_max_size(configuration: &TitanConfiguration, size: UVec2) -> bool {
+    let fits = |size: UVec2| {
+        (size + configuration.padding)
+            .map(|length| length.next_multiple_of(configuration.alignment))
+            .cmple(configuration.max_size)
+            .all()
+    };
+
+    fits(size) || (configuration.allow_rotation && fits(UVec2::new(size.y, size.x)))
+}
+
+/// Indices of all images in the order they are handed to the [`TextureAtlasBuilder`].
+fn packing_order(
+    sort_order: TitanSortOrder,
+    images: &[Image],
+    names: &HashMap<String, usize>,
+) -> Vec<usize> {
+    let mut order = (0..images.len()).collect::<Vec<_>>();
+    match sort_order {
+        TitanSortOrder::AsDeclared => {}
+        TitanSortOrder::BySizeDesc => order.sort_by_key(|&index| {
+            std::cmp::Reverse(u64::from(images[index].width()) * u64::from(images[index].height()))
+        }),
+        TitanSortOrder::ByName => {
+            /* A sprite with several names is sorted by the smallest one */
+            let mut sprite_names = vec![None; images.len()];
+            for (name, &index) in names {
+                let sprite_name: &mut Option<&str> = &mut sprite_names[index];
+                if sprite_name.is_none_or(|sprite_name| name.as_str() < sprite_name) {
+                    *sprite_name = Some(name.as_str());
+                }
+            }
+            order.sort_by_key(|&index| (sprite_names[index].is_none(), sprite_names[index]));
+        }
+    }
+
+    order
+}
+
+/// Stacks all images into a 2D array texture with one layer per image, converted to the configured format.
+pub(crate) fn build_array_texture(
+    configuration: &TitanConfiguration,
+    images: Vec<Image>,
+) -> Result<(TextureAtlasLayout, Image), SpriteSheetLoaderError> {
+    let size = images[0].size();
+    let mut layout = TextureAtlasLayout::new_empty(size);
+    let mut data = Vec::with_capacity(images.len() * images[0].data.len());
+    for (index, image) in images.iter().enumerate() {
+        if image.size() != size {
+            return Err(SpriteSheetLoaderError::ArraySizeError(
+                index,
+                image.size(),
+                size,
+            ));
+        }
+        if image.texture_descriptor.format == configuration.format {
+            data.extend_from_slice(&image.data);
+        } else if let Some(image) = configuration
+            .auto_format_conversion
+            .then(|| image.convert(configuration.format))
+            .flatten()
+        {
+            data.extend(image.data);
+        } else {
+            return Err(TextureAtlasBuilderError::WrongFormat.into());
+        }
+        layout.add_texture(URect::from_corners(UVec2::ZERO, size));
+    }
+
+    let mut texture = Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: images.len() as u32,
+        },
+        TextureDimension::D2,
+        data,
+        configuration.format,
+        RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD,
+    );
+    texture.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::D2Array),
+        ..Default::default()
+    });
+
+    Ok((layout, texture))
+}
+
+/// Resolves the frames of all animations.
+///
+/// A frame is displayed for the duration of its animation, the duration of its sprite or zero, in that order.
+pub(crate) fn build_animations(
+    titan_animations: Vec<TitanAnimation>,
+    durations: &[Option<Duration>],
+    names: &HashMap<String, usize>,
+) -> Result<HashMap<String, AnimationClip>, SpriteSheetLoaderError> {
+    let mut animations = HashMap::new();
+    for titan_animation in titan_animations {
+        let animation_duration = titan_animation
+            .duration_ms
+            .map(|ms| Duration::from_millis(ms.into()));
+        let frame_duration = match titan_animation.frame_rate {
+            Some(frame_rate) if !(frame_rate.is_finite() && frame_rate > 0.0) => {
+                return Err(SpriteSheetLoaderError::InvalidFrameRateError(
+                    frame_rate,
+                    titan_animation.name,
+                ));
+            }
+            frame_rate => frame_rate.map(|frame_rate| Duration::from_secs_f32(frame_rate.recip())),
+        };
+        let frame_indices = titan_animation
+            .frames
+            .into_iter()
+            .map(|frame| match frame {
+                TitanFrame::Index(index) => Ok(index),
+                TitanFrame::Name(name) => names.get(&name).copied().ok_or_else(|| {
+                    SpriteSheetLoaderError::UnknownAnimationFrameError(
+                        titan_animation.name.clone(),
+                        name,
+                    )
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let frame_indices = match titan_animation.playback {
+            TitanPlayback::PingPong => ping_pong(frame_indices),
+            _ => frame_indices,
+        };
+        let frames = frame_indices
+            .iter()
+            .map(|&index| {
+                let duration = durations.get(index).ok_or_else(|| {
+                    InvalidAnimationError(titan_animation.name.clone(), index, durations.len())
+                })?;
+
+                Ok(AnimationFrame {
+                    index,
+                    duration: animation_duration
+                        .or(*duration)
+                        .or(frame_duration)
+                        .unwrap_or_default(),
+                })
+            })
+            .collect::<Result<_, InvalidAnimationError>>()?;
+
+        if animations.contains_key(&titan_animation.name) {
+            return Err(DuplicateNameError(titan_animation.name).into());
+        }
+        animations.insert(
+            titan_animation.name,
+            AnimationClip {
+                frames,
+                playback: titan_animation.playback.into(),
+            },
+        );
+    }
+
+    Ok(animations)
+}
+
+/// Appends the way back to `frames`, without repeating the first and the last frame.
+pub(crate) fn ping_pong(frames: Vec<usize>) -> Vec<usize> {
+    let back = frames
+        .iter()
+        .rev()
+        .skip(1)
+        .take(frames.len().saturating_sub(2))
+        .copied()
+        .collect::<Vec<_>>();
+    [frames, back].concat()
+}
+
+/// Loads all images in order through the asset pipeline.
+///
+/// Every image is loaded with its own nested immediate load, so `.meta` settings and registered image loaders apply.
+/// The loads are polled concurrently and their results are returned in the order of `sources`.
+pub(crate) async fn decode_images(
+    load_context: &LoadContext<'_>,
+    sources: &[(&str, Vec<u8>)],
+    single_channel: bool,
+    progress: &TitanLoadProgress,
+) -> Vec<Result<Image, SpriteSheetLoaderError>> {
+    progress
+        .0
+        .images
+        .fetch_add(sources.len(), Ordering::Relaxed);
+
+    join_all(sources.iter().map(|(path, bytes)| async move {
+        let image = decode_image(
+            load_context.begin_labeled_asset(),
+            path,
+            bytes,
+            single_channel,
+        )
+        .await;
+        progress.0.decoded.fetch_add(1, Ordering::Relaxed);
+        image
+    }))
+    .await
+}
+
+/// Loads a single image with an immediate nested load.
+///
+/// With `single_channel` pngs are decoded directly instead, so grayscale ones are not expanded to RGBA.
+async fn decode_image(
+    mut load_context: LoadContext<'_>,
+    path: &str,
+    bytes: &[u8],
+    single_channel: bool,
+) -> Result<Image, SpriteSheetLoaderError> {
+    if single_channel && image::ImageFormat::from_path(path).ok() == Some(image::ImageFormat::Png) {
+        return image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+            .map(|dynamic_image| from_dynamic(dynamic_image, true))
+            .map_err(|error| SpriteSheetLoaderError::PngError(path.to_string(), error));
+    }
+
+    load_context
+        .loader()
+        .immediate()
+        .load::<Image>(AssetPath::from_path(Path::new(path)))
+        .await
+        .map(|image| image.take())
+        /* Point out a missing image format feature instead of the failed load */
+        .map_err(|error| image_format(path).err().unwrap_or_else(|| error.into()))
+}
+
+/// Polls all futures concurrently and returns their outputs in order.
+async fn join_all<F: Future>(futures: impl IntoIterator<Item = F>) -> Vec<F::Output> {
+    let mut futures = futures.into_iter().map(Box::pin).collect::<Vec<_>>();
+    let mut outputs = futures.iter().map(|_| None).collect::<Vec<_>>();
+    poll_fn(|context| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if output.is_none() {
+                match future.as_mut().poll(context) {
+                    Poll::Ready(value) => *output = Some(value),
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+
+    outputs
+        .into_iter()
+        .map(|output| output.expect("Every future is ready"))
+        .collect()
+}
+
+/// The image format given by the extension of `path`, if it is enabled in bevy.
+pub(crate) fn image_format(path: &str) -> Result<ImageFormat, SpriteSheetLoaderError> {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .ok_or_else(|| {
+            SpriteSheetLoaderError::NotAnImageError(
+                path.to_string(),
+                "it has no file extension".to_string(),
+            )
+        })?;
+
+    match ImageFormat::from_extension(extension) {
+        Some(format) => Ok(format),
+        /* Formats that are not enabled in bevy are still known to the image crate */
+        None if image::ImageFormat::from_extension(extension).is_some()
+            || matches!(extension.to_ascii_lowercase().as_str(), "ktx2" | "basis") =>
+        {
+            Err(SpriteSheetLoaderError::UnsupportedImageFormat {
+                path: path.to_string(),
+                ext: extension.to_string(),
+            })
+        }
+        None => Err(SpriteSheetLoaderError::NotAnImageError(
+            path.to_string(),
+            format!("{extension} is not the extension of any image format"),
+        )),
+    }
+}
+
+/// Whether the file name of `path` ends with `.{extension}`.
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .is_some_and(|file_name| file_name.ends_with(&format!(".{extension}")))
+}
+
+/// Decodes the inline data of all entries of a validated manifest.
+///
+/// The path of each of these entries is set to a placeholder, which is also the key of its decoded image.
+pub(crate) fn decode_inline_images(
+    titan_entries: &mut [TitanEntry],
+) -> Result<HashMap<String, Image>, SpriteSheetLoaderError> {
+    let mut inline_images = HashMap::new();
+    for (index, titan_entry) in titan_entries.iter_mut().enumerate() {
+        let Some(TitanData::Inline(base64_data, format, size)) = &titan_entry.data else {
+            continue;
+        };
+        let error = |message: String| InlineDataError(index, message);
+
+        let data = BASE64_STANDARD
+            .decode(base64_data)
+            .map_err(|decode_error| error(decode_error.to_string()))?;
+        let (block_width, block_height) = format.block_dimensions();
+        let expected_len = format
+            .block_copy_size(None)
+            .map(|block_size| {
+                size.x.div_ceil(block_width) as usize
+                    * size.y.div_ceil(block_height) as usize
+                    * block_size as usize
+            })
+            .ok_or_else(|| error(format!("Texture format {format:?} is not supported")))?;
+        if data.len() != expected_len || size.cmpeq(UVec2::ZERO).any() {
+            return Err(error(format!(
+                "Expected {expected_len} bytes for size {size} and texture format {format:?}, but got {}",
+                data.len()
+            ))
+            .into());
+        }
+
+        let image = Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            *format,
+            RenderAssetUsages::default(),
+        );
+        titan_entry.path = format!("<inline data of entry {index}>");
+        inline_images.insert(titan_entry.path.clone(), image);
+    }
+
+    Ok(inline_images)
+}
+
+/// All distinct image paths in order of their first occurrence. Entries with inline data are skipped.
+pub(crate) fn source_paths(titan_entries: &[TitanEntry]) -> Vec<&str> {
+    let mut unique_paths = HashSet::new();
+    titan_entries
+        .iter()
+        .filter(|titan_entry| titan_entry.data.is_none())
+        .map(|titan_entry| titan_entry.path.as_str())
+        .filter(|path| unique_paths.insert(*path))
+        .collect()
+}
+
+/// Extracts all sprites of a sprite sheet and returns their rects within the image.
+fn push_textures(
+    images: &mut Vec<Image>,
+    sprite_sheet: TitanSpriteSheet,
+    path: &str,
+    index: usize,
+    texture: Cow<Image>,
+    clamp_rects: bool,
+    coordinate_origin: TitanCoordinateOrigin,
+) -> Result<Vec<URect>, SpriteSheetLoaderError> {
+    if let TitanSpriteSheet::None = sprite_sheet {
+        let rect = URect::from_corners(UVec2::ZERO, texture.size());
+        images.push(texture.into_owned());
+        return Ok(vec![rect]);
+    }
+
+    let mut rects = sprite_rects(&sprite_sheet, &texture, path, index, clamp_rects)?;
+    let bottom_left = coordinate_origin == TitanCoordinateOrigin::BottomLeft;
+    if bottom_left {
+        rects_to_top_left(&sprite_sheet, &mut rects, texture.height());
+    }
+    if let TitanSpriteSheet::HeterogeneousSubpixel {
+        rects: subpixel_rects,
+        offset,
+    } = &sprite_sheet
+    {
+        let height = texture.height() as f32;
+        for (subpixel_rect, rect) in subpixel_rects.iter().zip(&rects) {
+            let mut subpixel_rect = subpixel_rect.rect(*offset);
+            if bottom_left {
+                subpixel_rect = Rect::new(
+                    subpixel_rect.min.x,
+                    height - subpixel_rect.max.y,
+                    subpixel_rect.max.x,
+                    height - subpixel_rect.min.y,
+                );
+            }
+            /* The pixels covering the subpixel rect are already clamped to the image */
+            let subpixel_rect = subpixel_rect.intersect(rect.as_rect());
+            let image =
+                extract_texture_from_subpixel_rect(&texture, subpixel_rect).ok_or_else(|| {
+                    SpriteSheetLoaderError::SubpixelFormatError(
+                        path.to_string(),
+                        texture.texture_descriptor.format,
+                    )
+                })?;
+
+            images.push(image);
+        }
+
+        return Ok(rects);
+    }
+    for &rect in &rects {
+        let image = extract_texture_from_rect(&texture, rect)
+            .ok_or_else(|| InvalidRectError(rect.min, rect.max, path.to_string(), index))?;
+
+        images.push(image);
+    }
+
+    Ok(rects)
+}
+
+/// Converts the rects of a sprite sheet, measured from the bottom left corner of an image of the given height,
+/// to the top left origin. Color grids are detected in the image and are measured from the top left already.
+fn rects_to_top_left(sprite_sheet: &TitanSpriteSheet, rects: &mut [URect], height: u32) {
+    if let TitanSpriteSheet::ColorGrid { .. } = sprite_sheet {
+        return;
+    }
+
+    for rect in rects {
+        *rect = rect_to_top_left(*rect, height).expect("Rects are within the image");
+    }
+}
+
+/// Mirrors `rect` vertically within an image of the given height. Returns `None` if it exceeds the image.
+fn rect_to_top_left(rect: URect, height: u32) -> Option<URect> {
+    Some(URect::new(
+        rect.min.x,
+        height.checked_sub(rect.max.y)?,
+        rect.max.x,
+        height.checked_sub(rect.min.y)?,
+    ))
+}
+
+/// Number of sprites and their combined area of a sprite sheet within an image of the given size,
+/// without computing any rect.
+fn sprite_sheet_extent(
+    sprite_sheet: &TitanSpriteSheet,
+    size: UVec2,
+    clamp_rects: bool,
+) -> (u64, u64) {
+    let area = |size: UVec2| u64::from(size.x) * u64::from(size.y);
+    match sprite_sheet {
+        TitanSpriteSheet::None => (1, area(size)),
+        TitanSpriteSheet::Homogeneous {
+            tile_size,
+            columns,
+            rows,
+            ..
+        } => {
+            let len = u64::from(*columns) * u64::from(*rows);
+            (len, len.saturating_mul(area(*tile_size)))
+        }
+        TitanSpriteSheet::Heterogeneous { rects, .. } => (
+            rects.len() as u64,
+            rects
+                .iter()
+                .fold(0, |acc: u64, rect| acc.saturating_add(area(rect.size))),
+        ),
+        TitanSpriteSheet::HeterogeneousSubpixel { rects, .. } => (
+            rects.len() as u64,
+            rects.iter().fold(0, |acc: u64, rect| {
+                acc.saturating_add(area(rect.size.round().as_uvec2().max(UVec2::ONE)))
+            }),
+        ),
+        TitanSpriteSheet::Grid {
+            tile_size,
+            padding,
+            border_padding,
+            offset,
+        } => {
+            /* Invalid grids are rejected later on */
+            let len = grid_size(
+                size,
+                *tile_size,
+                *padding,
+                *border_padding,
+                *offset,
+                clamp_rects,
+            )
+            .map_or(0, area);
+            (len, len.saturating_mul(area(*tile_size)))
+        }
+        /* The cells are only known after scanning the image, they can not exceed it */
+        TitanSpriteSheet::ColorGrid { .. } => (1, area(size)),
+    }
+}
+
+/// All rects of a sprite sheet within an image.
+///
+/// Rects that exceed the image are an error, unless `clamp_rects` is set. Then they are clamped to the image
+/// and grids do not need to divide the image evenly.
+fn sprite_rects(
+    sprite_sheet: &TitanSpriteSheet,
+    image: &Image,
+    path: &str,
+    index: usize,
+    clamp_rects: bool,
+) -> Result<Vec<URect>, SpriteSheetLoaderError> {
+    let size = image.size();
+    let mut rects = match *sprite_sheet {
+        TitanSpriteSheet::None => vec![URect::from_corners(UVec2::ZERO, size)],
+        TitanSpriteSheet::Homogeneous {
+            tile_size,
+            columns,
+            rows,
+            padding,
+            padding_rect,
+            border_padding,
+            offset,
+        } => {
+            let grid_size = UVec2::new(columns, rows);
+            /* Space between two neighboring tiles and the padding before the first one */
+            let (gap, leading_padding) = match padding_rect {
+                Some(padding_rect) => (
+                    UVec2::new(
+                        padding_rect.left + padding_rect.right,
+                        padding_rect.top + padding_rect.bottom,
+                    ),
+                    UVec2::new(padding_rect.left, padding_rect.top),
+                ),
+                None => (2 * padding, padding),
+            };
+            let border_padding = border_padding.unwrap_or(leading_padding);
+            /* Fail once for the whole grid instead of for its first rect that does not fit */
+            if !clamp_rects && grid_size.cmpgt(UVec2::ZERO).all() {
+                let required_size = offset + border_padding + grid_size * (tile_size + gap) - gap;
+                if required_size.cmpgt(size).any() {
+                    return Err(SpriteSheetLoaderError::GridOutOfBounds {
+                        tile_size,
+                        columns,
+                        rows,
+                        image_size: size,
+                        required_size,
+                        path: path.to_string(),
+                        index,
+                    });
+                }
+            }
+
+            grid_rects(tile_size, grid_size, gap, border_padding, offset)
+        }
+        TitanSpriteSheet::Heterogeneous { ref rects, offset } => rects
+            .iter()
+            .map(|rect| {
+                let min = rect.position + offset;
+                URect::from_corners(min, min + rect.size)
+            })
+            .collect(),
+        TitanSpriteSheet::HeterogeneousSubpixel { ref rects, offset } => rects
+            .iter()
+            .map(|rect| {
+                /* All pixels the rect covers at least partially */
+                let rect = rect.rect(offset);
+                URect::from_corners(rect.min.floor().as_uvec2(), rect.max.ceil().as_uvec2())
+            })
+            .collect(),
+        TitanSpriteSheet::Grid {
+            tile_size,
+            padding,
+            border_padding,
+            offset,
+        } => {
+            let grid_size = grid_size(
+                size,
+                tile_size,
+                padding,
+                border_padding,
+                offset,
+                clamp_rects,
+            )
+            .ok_or_else(|| InvalidGridError(size, tile_size, path.to_string(), index))?;
+
+            grid_rects(
+                tile_size,
+                grid_size,
+                2 * padding,
+                border_padding.unwrap_or(padding),
+                offset,
+            )
+        }
+        TitanSpriteSheet::ColorGrid {
+            gutter_color,
+            tolerance,
+        } => {
+            let rects = color_grid_rects(image, gutter_color, tolerance).ok_or_else(|| {
+                SpriteSheetLoaderError::ColorGridError(
+                    path.to_string(),
+                    image.texture_descriptor.format,
+                )
+            })?;
+            if rects.is_empty() {
+                warn!(
+                    "No cells detected in color grid of entry {} with image {}",
+                    index, path
+                );
+            }
+
+            rects
+        }
+    };
+
+    for rect in &mut rects {
+        if rect.max.cmpgt(size).any() {
+            let clamped_rect = URect::from_corners(rect.min.min(size), rect.max.min(size));
+            if !clamp_rects || clamped_rect.is_empty() {
+                return Err(InvalidRectError(rect.min, rect.max, path.to_string(), index).into());
+            }
+            warn!(
+                "Rect with min {} and max {} of entry {} is clamped to image {} with size {}",
+                rect.min, rect.max, index, path, size
+            );
+            *rect = clamped_rect;
+        }
+    }
+
+    Ok(rects)
+}
+
+/// Number of columns and rows of a grid that exactly fills an image of the given size.
+/// With `clamp_rects` a partial last column and row is included.
+fn grid_size(
+    size: UVec2,
+    tile_size: UVec2,
+    padding: UVec2,
+    border_padding: Option<UVec2>,
+    offset: UVec2,
+    clamp_rects: bool,
+) -> Option<UVec2> {
+    /* The border replaces the padding on the outside of the outer tiles */
+    let cell_size = tile_size + 2 * padding;
+    let border_size = 2 * border_padding.unwrap_or(padding);
+    let available_size = size.saturating_sub(offset) + 2 * padding;
+    if available_size.cmplt(border_size).any() {
+        return None;
+    }
+    let available_size = available_size - border_size;
+    if tile_size.cmpeq(UVec2::ZERO).any() || available_size.cmpeq(UVec2::ZERO).any() {
+        return None;
+    }
+    if clamp_rects {
+        return Some((available_size + cell_size - 1) / cell_size);
+    }
+    if available_size.cmplt(cell_size).any() || available_size % cell_size != UVec2::ZERO {
+        return None;
+    }
+
+    Some(available_size / cell_size)
+}
+
+/// Rects of a grid of `grid_size` tiles row by row, `gap` apart.
+fn grid_rects(
+    tile_size: UVec2,
+    grid_size: UVec2,
+    gap: UVec2,
+    border_padding: UVec2,
+    offset: UVec2,
+) -> Vec<URect> {
+    let mut rects = Vec::with_capacity((grid_size.x * grid_size.y) as usize);
+    for i in 0..grid_size.y {
+        for j in 0..grid_size.x {
+            let min = offset + border_padding + UVec2::new(j, i) * (tile_size + gap);
+            rects.push(URect::from_corners(min, min + tile_size));
+        }
+    }
+
+    rects
+}
+
+/// Trims the image in place if requested and returns the corresponding trim information.
+fn trim_image(image: &mut Image, trim_entry: bool, path: &str) -> SpriteTrim {
+    let original_size = image.size();
+    let mut offset = UVec2::ZERO;
+    if trim_entry {
+        match trim(image) {
+            Some((trimmed_image, trim_offset)) => {
+                *image = trimmed_image;
+                offset = trim_offset;
+            }
+            None => warn!(
+                "Can not trim image {} of format {:?}, because it has no unsigned 8 bit alpha channel",
+                path, image.texture_descriptor.format
+            ),
+        }
+    }
+
+    SpriteTrim {
+        original_size,
+        offset,
+    }
+}
+
+/// A single sprite is named as is, all sprites of a sprite sheet get their index within the sprite sheet appended.
+fn insert_names(
+    names: &mut HashMap<String, usize>,
+    name: String,
+    indices: std::ops::Range<usize>,
+    single: bool,
+) -> Result<(), DuplicateNameError> {
+    if single {
+        if names.insert(name.clone(), indices.start).is_some() {
+            return Err(DuplicateNameError(name));
+        }
+    } else {
+        for (i, index) in indices.enumerate() {
+            let name = format!("{name}_{i}");
+            if names.insert(name.clone(), index).is_some() {
+                return Err(DuplicateNameError(name));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Names every sprite with `prefix`, its index within `indices` and `suffix`, e.g. `frame0`.
+fn insert_prefixed_names(
+    names: &mut HashMap<String, usize>,
+    prefix: &str,
+    suffix: &str,
+    indices: std::ops::Range<usize>,
+) -> Result<(), DuplicateNameError> {
+    for (i, index) in indices.enumerate() {
+        insert_names(
+            names,
+            format!("{prefix}{i}{suffix}"),
+            index..index + 1,
+            true,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{ops::Deref, path::PathBuf, time::Instant};
+
+    use bevy::{
+        app::App,
+        asset::{AssetApp, AssetPlugin, AssetServer, Assets, LoadState},
+        core::TaskPoolPlugin,
+        image::{CompressedImageFormats, ImageLoader, ImageLoaderError, ImageLoaderSettings},
+        render::render_resource::{Extent3d, TextureDimension, TextureUsages},
+    };
+
+    use super::*;
+    use crate::{
+        export::{export_atlas, export_titan, ExportError},
+        import::ImportedRotation,
+        manifest::TitanSampler,
+        metadata::AnimationPlayback,
+    };
+
+    fn image(size: UVec2) -> Image {
+        Image::new_fill(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        )
+    }
+
+    /// Assets directory of a single test, which is deleted again when dropped.
+    struct TempAssets(PathBuf);
+
+    impl TempAssets {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("bevy_titan_{name}_{}", std::process::id()));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Deref for TempAssets {
+        type Target = Path;
+
+        fn deref(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempAssets {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// An app that loads titan files from a new [`TempAssets`] directory.
+    fn asset_app(name: &str, watch_for_changes: bool) -> (App, TempAssets) {
+        let assets = TempAssets::new(name);
+        let mut app = App::new();
+        app.add_plugins((
+            TaskPoolPlugin::default(),
+            AssetPlugin {
+                file_path: assets.to_string_lossy().into_owned(),
+                watch_for_changes_override: Some(watch_for_changes),
+                ..Default::default()
+            },
+            crate::SpriteSheetLoaderPlugin,
+        ))
+        .init_asset::<Image>()
+        .register_asset_loader(ImageLoader::new(CompressedImageFormats::NONE))
+        .init_asset::<TextureAtlasLayout>();
+
+        (app, assets)
+    }
+
+    /// Every pixel encodes its own position in the red and green channel.
+    fn positional_image(size: UVec2) -> Image {
+        let data = (0..size.y)
+            .flat_map(|y| (0..size.x).flat_map(move |x| [x as u8, y as u8, 0, 255]))
+            .collect();
+        Image::new(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        )
+    }
+
+    #[test]
+    fn sizes() {
+        let configuration = TitanConfiguration::default();
+        assert_eq!(configuration.initial_size, UVec2::new(256, 256));
+        assert!(validate_sizes(&configuration).is_ok());
+
+        let configuration = TitanConfiguration {
+            initial_size: UVec2::new(0, 256),
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_sizes(&configuration),
+            Err(SpriteSheetLoaderError::ZeroSizeError(_, _))
+        ));
+
+        let configuration = TitanConfiguration {
+            max_size: UVec2::new(2048, 0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            validate_sizes(&configuration),
+            Err(SpriteSheetLoaderError::ZeroSizeError(_, _))
+        ));
+    }
+
+    #[test]
+    fn always_pack() {
+        let configuration = TitanConfiguration {
+            always_pack: true,
+            ..Default::default()
+        };
+        let (layout, texture) =
+            build_texture_atlas(&configuration, &[image(UVec2::new(3, 5))]).unwrap();
+
+        assert_eq!(texture.size(), configuration.initial_size);
+        assert_eq!(layout.size, configuration.initial_size);
+        assert_eq!(layout.textures, vec![URect::new(0, 0, 3, 5)]);
+    }
+
+    #[test]
+    fn deduplicate() {
+        let configuration = TitanConfiguration {
+            deduplicate: true,
+            ..Default::default()
+        };
+        let images = vec![
+            image(UVec2::new(3, 5)),
+            image(UVec2::new(5, 3)),
+            image(UVec2::new(3, 5)),
+        ];
+        let (layout, _) = build_texture_atlas(&configuration, &images).unwrap();
+
+        assert_eq!(layout.textures.len(), 3);
+        assert_eq!(layout.textures[0], layout.textures[2]);
+        assert_ne!(layout.textures[0], layout.textures[1]);
+    }
+
+    #[test]
+    fn heterogeneous_rect_syntax() {
+        let sprite_sheet = ron::de::from_str::<TitanSpriteSheet>(
+            "Heterogeneous([((1, 2), (3, 4)), (name: \"b\", position: (5, 6), size: (7, 8), anchor: Some((0.5, 1.0)))])",
+        )
+        .unwrap();
+
+        let TitanSpriteSheet::Heterogeneous { rects, offset } = sprite_sheet else {
+            panic!("Expected Heterogeneous");
+        };
+        assert_eq!(rects[0].name, None);
+        assert_eq!(rects[0].position, UVec2::new(1, 2));
+        assert_eq!(rects[0].size, UVec2::new(3, 4));
+        assert_eq!(rects[1].name.as_deref(), Some("b"));
+        assert_eq!(rects[1].position, UVec2::new(5, 6));
+        assert_eq!(rects[1].size, UVec2::new(7, 8));
+        assert_eq!(rects[1].anchor, Some(Vec2::new(0.5, 1.0)));
+        assert_eq!(offset, UVec2::ZERO);
+
+        /* Rects with an offset are written as struct */
+        let TitanSpriteSheet::Heterogeneous { rects, offset } =
+            ron::de::from_str::<TitanSpriteSheet>(
+                "Heterogeneous((rects: [((1, 2), (3, 4))], offset: (5, 6)))",
+            )
+            .unwrap()
+        else {
+            panic!("Expected Heterogeneous");
+        };
+        assert_eq!(rects.len(), 1);
+        assert_eq!(rects[0].position, UVec2::new(1, 2));
+        assert_eq!(offset, UVec2::new(5, 6));
+        assert!(ron::de::from_str::<TitanSpriteSheet>(
+            "Heterogeneous((rects: [((1, 2), (3, 4))], border: (5, 6)))"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn heterogeneous_offset() {
+        let sheet = positional_image(UVec2::new(8, 6));
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"sheet.png\", sprite_sheet: Heterogeneous((rects: [((0, 0), (2, 2)), ((3, 1), (1, 1))], offset: (2, 2))))])",
+        )
+        .unwrap();
+
+        let packed_atlas =
+            pack_atlas(titan, HashMap::from([("sheet.png".to_string(), sheet)])).unwrap();
+
+        for (rect, position) in packed_atlas
+            .layout
+            .textures
+            .iter()
+            .zip([UVec2::new(2, 2), UVec2::new(5, 3)])
+        {
+            let pixel = packed_atlas
+                .texture
+                .pixel_bytes(rect.min.extend(0))
+                .unwrap();
+            assert_eq!(pixel, [position.x as u8, position.y as u8, 0, 255]);
+        }
+        assert_eq!(
+            packed_atlas.source_rects,
+            [
+                SpriteSource {
+                    path: "sheet.png".to_string(),
+                    rect: URect::new(2, 2, 4, 4),
+                    transformed: false,
+                },
+                SpriteSource {
+                    path: "sheet.png".to_string(),
+                    rect: URect::new(5, 3, 6, 4),
+                    transformed: false,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let titan = ron::de::from_bytes::<Titan>(include_bytes!(
+            "../assets/composite-texture-atlas.titan.ron"
+        ))
+        .unwrap();
+        let serialized = ron::ser::to_string(&titan).unwrap();
+        let round_tripped = ron::de::from_str::<Titan>(&serialized).unwrap();
+
+        assert_eq!(ron::ser::to_string(&round_tripped).unwrap(), serialized);
+    }
+
+    #[test]
+    fn texture_packer_import() {
+        let imported_atlas = texture_packer::parse(
+            br#"{
+                "frames": {
+                    "b.png": {"frame": {"x": 0, "y": 0, "w": 4, "h": 2}, "rotated": true, "trimmed": false},
+                    "a.png": {
+                        "frame": {"x": 2, "y": 0, "w": 3, "h": 3}, "rotated": false, "trimmed": true,
+                        "spriteSourceSize": {"x": 1, "y": 2, "w": 3, "h": 3}, "sourceSize": {"w": 5, "h": 6}
+                    }
+                },
+                "meta": {"image": "atlas.png", "size": {"w": 8, "h": 8}}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(imported_atlas.image, "atlas.png");
+        assert_eq!(imported_atlas.sprites.len(), 2);
+        assert_eq!(imported_atlas.sprites[0].name.as_deref(), Some("b.png"));
+        assert_eq!(
+            imported_atlas.sprites[0].rotation,
+            ImportedRotation::Clockwise
+        );
+        assert_eq!(imported_atlas.sprites[1].rect, URect::new(2, 0, 5, 3));
+        assert_eq!(
+            imported_atlas.sprites[1].trim,
+            SpriteTrim {
+                original_size: UVec2::new(5, 6),
+                offset: UVec2::new(1, 2),
+            }
+        );
+    }
+
+    #[test]
+    fn aseprite_import() {
+        let imported_atlas = aseprite::parse(
+            br#"{
+                "frames": [
+                    {"filename": "a 0", "frame": {"x": 0, "y": 0, "w": 2, "h": 2}, "duration": 100},
+                    {"filename": "a 1", "frame": {"x": 2, "y": 0, "w": 2, "h": 2}, "duration": 200},
+                    {"filename": "a 2", "frame": {"x": 4, "y": 0, "w": 2, "h": 2}, "duration": 300}
+                ],
+                "meta": {
+                    "image": "a.png",
+                    "frameTags": [
+                        {"name": "forward", "from": 0, "to": 2, "direction": "forward"},
+                        {"name": "reverse", "from": 0, "to": 2, "direction": "reverse"},
+                        {"name": "pingpong", "from": 0, "to": 2, "direction": "pingpong"}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(imported_atlas.sprites.len(), 3);
+        assert_eq!(imported_atlas.sprites[2].name.as_deref(), Some("a 2"));
+        assert_eq!(
+            imported_atlas.sprites[1].duration,
+            Some(Duration::from_millis(200))
+        );
+        let frames = imported_atlas
+            .animations
+            .iter()
+            .map(|animation| (animation.name.as_str(), animation.frames.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            frames,
+            vec![
+                ("forward", [0, 1, 2].map(TitanFrame::Index).to_vec()),
+                ("reverse", [2, 1, 0].map(TitanFrame::Index).to_vec()),
+                ("pingpong", [0, 1, 2, 1].map(TitanFrame::Index).to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn libgdx_import() {
+        let imported_atlas = libgdx::parse(
+            "
+atlas.png
+size: 64, 64
+format: RGBA8888
+filter: Nearest, Nearest
+repeat: none
+run
+  rotate: false
+  xy: 10, 0
+  size: 8, 8
+  orig: 10, 12
+  offset: 1, 2
+  index: 1
+run
+  rotate: true
+  xy: 0, 0
+  size: 8, 10
+  orig: 8, 10
+  offset: 0, 0
+  index: 0
+idle
+bounds:20,0,4,4
+",
+        )
+        .unwrap();
+
+        assert_eq!(imported_atlas.image, "atlas.png");
+        let names = imported_atlas
+            .sprites
+            .iter()
+            .map(|sprite| sprite.name.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["run_1", "run_0", "idle"]);
+        assert_eq!(
+            imported_atlas.sprites[0].trim,
+            SpriteTrim {
+                original_size: UVec2::new(10, 12),
+                offset: UVec2::new(1, 2),
+            }
+        );
+        assert_eq!(
+            imported_atlas.sprites[1].rotation,
+            ImportedRotation::Counterclockwise
+        );
+        assert_eq!(imported_atlas.sprites[2].rect, URect::new(20, 0, 24, 4));
+        assert_eq!(imported_atlas.animations.len(), 1);
+        assert_eq!(imported_atlas.animations[0].name, "run");
+        assert_eq!(
+            imported_atlas.animations[0].frames,
+            [TitanFrame::Index(1), TitanFrame::Index(0)]
+        );
+    }
+
+    #[test]
+    fn allow_rotation() {
+        let configuration = TitanConfiguration {
+            initial_size: UVec2::new(8, 8),
+            allow_rotation: true,
+            ..Default::default()
+        };
+        let images = vec![image(UVec2::new(8, 4)), image(UVec2::new(4, 8))];
+        let (layout, _, rotations) = build_rotated_texture_atlas(&configuration, &images).unwrap();
+
+        assert_eq!(rotations, vec![false, true]);
+        assert_eq!(layout.size, UVec2::new(8, 8));
+        assert_eq!(layout.textures[1].size(), UVec2::new(8, 4));
+    }
+
+    #[test]
+    fn reload_on_source_change() {
+        let (mut app, assets) = asset_app("reload", true);
+        let write_png = |name: &str, size: u32| {
+            image::RgbaImage::new(size, size)
+                .save(assets.join(name))
+                .unwrap();
+        };
+        write_png("a.png", 4);
+        write_png("b.png", 4);
+        std::fs::write(
+            assets.join("reload.titan.ron"),
+            "(configuration: (always_pack: false), textures: [(path: \"a.png\"), (path: \"b.png\")])",
+        )
+        .unwrap();
+
+        let handle = app
+            .world()
+            .resource::<AssetServer>()
+            .load::<TextureAtlas>("reload.titan.ron");
+        let layout_size = |app: &mut App| {
+            let texture_atlas = app
+                .world()
+                .resource::<Assets<TextureAtlas>>()
+                .get(&handle)?;
+            let layouts = app.world().resource::<Assets<TextureAtlasLayout>>();
+            Some(layouts.get(&texture_atlas.layout)?.textures[1].size())
+        };
+        let update_until = |app: &mut App, condition: &dyn Fn(&mut App) -> bool| {
+            let start = Instant::now();
+            while !condition(app) {
+                assert!(start.elapsed() < Duration::from_secs(30), "Timed out");
+                app.update();
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        };
+
+        update_until(&mut app, &|app| {
+            let load_state = app.world().resource::<AssetServer>().load_state(&handle);
+            assert!(!matches!(load_state, LoadState::Failed(_)));
+            load_state.is_loaded()
+        });
+        assert_eq!(layout_size(&mut app), Some(UVec2::new(4, 4)));
+        let texture_atlases = app.world().resource::<Assets<TextureAtlas>>();
+        let texture_atlas = texture_atlases.get(&handle).unwrap();
+        assert_eq!(texture_atlas.frame_count, 2);
+        let images = app.world().resource::<Assets<Image>>();
+        assert_eq!(
+            Some(texture_atlas.atlas_size),
+            images.get(&texture_atlas.texture).map(Image::size)
+        );
+        let sprite = texture_atlas.to_sprite(1);
+        assert_eq!(sprite.image, texture_atlas.texture);
+        assert_eq!(sprite.texture_atlas.map(|atlas| atlas.index), Some(1));
+
+        /* Changing any source image reloads the titan ron file */
+        write_png("b.png", 8);
+        update_until(&mut app, &|app| layout_size(app) == Some(UVec2::new(8, 8)));
+    }
+
+    #[test]
+    fn source_paths_are_distinct() {
+        let titan_entries = ron::de::from_str::<Vec<TitanEntry>>(
+            "[(path: \"a.png\"), (path: \"b.png\"), (path: \"a.png\"), (path: \"c.png\"), (path: \"b.png\")]",
+        )
+        .unwrap();
+
+        assert_eq!(
+            source_paths(&titan_entries),
+            vec!["a.png", "b.png", "c.png"]
+        );
+    }
+
+    /// An image loader that counts how often every path is loaded.
+    #[derive(Clone, Default)]
+    struct CountingImageLoader(Arc<std::sync::Mutex<HashMap<String, usize>>>);
+
+    impl AssetLoader for CountingImageLoader {
+        type Asset = Image;
+        type Settings = ImageLoaderSettings;
+        type Error = ImageLoaderError;
+
+        async fn load(
+            &self,
+            reader: &mut dyn Reader,
+            settings: &ImageLoaderSettings,
+            load_context: &mut LoadContext<'_>,
+        ) -> Result<Image, ImageLoaderError> {
+            *self
+                .0
+                .lock()
+                .unwrap()
+                .entry(load_context.path().to_string_lossy().into_owned())
+                .or_default() += 1;
+            ImageLoader::new(CompressedImageFormats::NONE)
+                .load(reader, settings, load_context)
+                .await
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["png"]
+        }
+    }
+
+    #[test]
+    fn sources_are_loaded_once() {
+        let (mut app, assets) = asset_app("load_once", false);
+        let loader = CountingImageLoader::default();
+        app.register_asset_loader(loader.clone());
+        for (name, width) in [("a", 2), ("b", 3)] {
+            image::RgbaImage::new(width, 2)
+                .save(assets.join(format!("{name}.png")))
+                .unwrap();
+        }
+        std::fs::write(
+            assets.join("once.titan.ron"),
+            "(textures: [(path: \"a.png\"), (path: \"b.png\"), (path: \"a.png\", sprite_sheet: Homogeneous(tile_size: (1, 2), columns: 2, rows: 1))])",
+        )
+        .unwrap();
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle = asset_server.load::<TextureAtlas>("once.titan.ron");
+        let start = Instant::now();
+        while !asset_server.is_loaded_with_dependencies(&handle) {
+            assert!(start.elapsed() < Duration::from_secs(30), "Timed out");
+            assert!(!asset_server.load_state(&handle).is_failed());
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let texture_atlas = app
+            .world()
+            .resource::<Assets<TextureAtlas>>()
+            .get(&handle)
+            .unwrap();
+        assert_eq!(texture_atlas.frame_count, 4);
+        assert_eq!(
+            *loader.0.lock().unwrap(),
+            HashMap::from([("a.png".to_string(), 1), ("b.png".to_string(), 1)])
+        );
+    }
+
+    #[test]
+    fn animation_label() {
+        let (mut app, assets) = asset_app("animation", false);
+        for (name, width) in [("walk", 2), ("idle", 1)] {
+            image::RgbaImage::new(width, 2)
+                .save(assets.join(format!("{name}.png")))
+                .unwrap();
+        }
+        std::fs::write(
+            assets.join("hero.titan.ron"),
+            "(textures: [(path: \"walk.png\", sprite_sheet: Grid(tile_size: (1, 2)), duration_ms: Some(100)), (path: \"idle.png\"), (path: \"idle.png\", duration_ms: Some(50))])",
+        )
+        .unwrap();
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle = asset_server.load::<TextureAtlasAnimation>("hero.titan.ron#animation");
+        let start = Instant::now();
+        while !asset_server.is_loaded_with_dependencies(&handle) {
+            assert!(start.elapsed() < Duration::from_secs(30), "Timed out");
+            assert!(!asset_server.load_state(&handle).is_failed());
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        /* Only sprites with a duration, in declaration order */
+        let animation = app
+            .world()
+            .resource::<Assets<TextureAtlasAnimation>>()
+            .get(&handle)
+            .unwrap();
+        assert_eq!(
+            animation
+                .iter()
+                .map(|frame| (frame.index, frame.duration.as_millis()))
+                .collect::<Vec<_>>(),
+            [(0, 100), (1, 100), (3, 50)]
+        );
+    }
+
+    #[test]
+    fn animations_label() {
+        let (mut app, assets) = asset_app("animations", false);
+        for (name, width) in [("walk", 2), ("idle", 1)] {
+            image::RgbaImage::new(width, 2)
+                .save(assets.join(format!("{name}.png")))
+                .unwrap();
+        }
+        std::fs::write(
+            assets.join("hero.titan.ron"),
+            "(textures: [(path: \"walk.png\", sprite_sheet: Grid(tile_size: (1, 2)), name_prefix: Some(\"walk\"), duration_ms: Some(100)), (path: \"idle.png\", name: Some(\"idle\"))], animations: [(name: \"walk\", frames: [\"walk1\", \"walk0\"]), (name: \"blink\", frames: [2, \"idle\"], duration_ms: Some(30))])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("unknown.titan.ron"),
+            "(textures: [(path: \"idle.png\", name: Some(\"idle\"))], animations: [(name: \"jump\", frames: [\"idle\", \"fall\"])])",
+        )
+        .unwrap();
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle = asset_server.load::<TextureAtlasAnimations>("hero.titan.ron#animations");
+        let unknown_handle = asset_server.load::<TextureAtlas>("unknown.titan.ron");
+        let start = Instant::now();
+        while !(asset_server.is_loaded_with_dependencies(&handle)
+            && asset_server.load_state(&unknown_handle).is_failed())
+        {
+            assert!(start.elapsed() < Duration::from_secs(30), "Timed out");
+            assert!(!asset_server.load_state(&handle).is_failed());
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        /* Frames in the order of the animation, with the duration of the animation or of their entry */
+        let animations = app
+            .world()
+            .resource::<Assets<TextureAtlasAnimations>>()
+            .get(&handle)
+            .unwrap();
+        let frames = |name: &str| {
+            animations[name]
+                .iter()
+                .map(|frame| (frame.index, frame.duration.as_millis()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(animations.len(), 2);
+        assert_eq!(frames("walk"), [(1, 100), (0, 100)]);
+        assert_eq!(frames("blink"), [(2, 30), (2, 30)]);
+
+        let LoadState::Failed(error) = asset_server.load_state(&unknown_handle) else {
+            unreachable!();
+        };
+        assert!(error.to_string().contains(
+            "Animation jump references sprite fall, but there is no sprite with this name"
+        ));
+    }
+
+    #[test]
+    fn always_pack_disabled() {
+        let configuration = TitanConfiguration {
+            always_pack: false,
+            ..Default::default()
+        };
+        let (layout, texture) =
+            build_texture_atlas(&configuration, &[image(UVec2::new(3, 5))]).unwrap();
+
+        assert_eq!(texture.size(), UVec2::new(3, 5));
+        assert_eq!(layout.size, UVec2::new(3, 5));
+        assert_eq!(layout.textures, vec![URect::new(0, 0, 3, 5)]);
+    }
+
+    #[test]
+    fn format_mismatch() {
+        let titan = |on_format_mismatch| Titan {
+            version: None,
+            extends: None,
+            configuration: TitanConfiguration {
+                format: TextureFormat::Rgba8Unorm,
+                on_format_mismatch,
+                ..Default::default()
+            },
+            textures: vec![ron::de::from_str("(path: \"a.png\")").unwrap()],
+            animations: Vec::new(),
+        };
+        let source_images = || HashMap::from([("a.png".to_string(), image(UVec2::new(3, 5)))]);
+
+        assert!(pack_atlas(titan(TitanFormatMismatch::Convert), source_images()).is_ok());
+        assert!(matches!(
+            pack_atlas(titan(TitanFormatMismatch::Error), source_images()),
+            Err(SpriteSheetLoaderError::FormatMismatch {
+                expected: TextureFormat::Rgba8Unorm,
+                found: TextureFormat::Rgba8UnormSrgb,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn not_enough_space() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (initial_size: (64, 64), max_size: (64, 64)), textures: [(path: \"a.png\"), (path: \"a.png\")])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([("a.png".to_string(), image(UVec2::new(50, 50)))]);
+
+        let Err(SpriteSheetLoaderError::NotEnoughSpaceError(error)) =
+            pack_atlas(titan, source_images)
+        else {
+            panic!("Expected NotEnoughSpaceError");
+        };
+        assert_eq!(
+            error.to_string(),
+            "2 sprites with a total area of 5000 pixels do not fit into max size [64, 64], consider raising max_size"
+        );
+    }
+
+    #[test]
+    fn inline_data() {
+        /* A single red pixel followed by a single blue pixel */
+        let mut titan = ron::de::from_str::<Titan>(
+            "(textures: [(data: Some(Inline(\"/wAA/wAA//8=\", \"rgba8unorm-srgb\", (2, 1))))])",
+        )
+        .unwrap();
+        validate(&titan).unwrap();
+
+        let source_images = decode_inline_images(&mut titan.textures).unwrap();
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+        assert_eq!(packed_atlas.layout.textures, vec![URect::new(0, 0, 2, 1)]);
+
+        let both = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"a.png\", data: Some(Inline(\"\", \"rgba8unorm-srgb\", (0, 0))))])",
+        )
+        .unwrap();
+        assert!(matches!(
+            validate(&both),
+            Err(SpriteSheetLoaderError::EntrySourceError(0))
+        ));
+    }
+
+    #[test]
+    fn repack_disabled() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (repack: false), textures: [(path: \"sheet.png\", sprite_sheet: Heterogeneous((rects: [((0, 0), (2, 2)), (name: \"b\", position: (3, 1), size: (1, 1))], offset: (2, 2))))])",
+        )
+        .unwrap();
+        validate(&titan).unwrap();
+        let source_images = HashMap::from([("sheet.png".to_string(), image(UVec2::new(8, 6)))]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(packed_atlas.texture.size(), UVec2::new(8, 6));
+        assert_eq!(
+            packed_atlas.layout.textures,
+            vec![URect::new(2, 2, 4, 4), URect::new(5, 3, 6, 4)]
+        );
+        assert_eq!(packed_atlas.names["b"], 1);
+        assert_eq!(packed_atlas.trims[1].original_size, UVec2::ONE);
+    }
+
+    #[test]
+    fn override_every_field() {
+        let configuration = TitanConfiguration::default();
+        let serde_json::Value::Object(fields) = serde_json::to_value(&configuration).unwrap()
+        else {
+            panic!("Expected an object");
+        };
+
+        for field in fields.keys() {
+            assert!(
+                TitanConfiguration::default().override_field(&configuration, field),
+                "{field} can not be extended"
+            );
+        }
+        assert!(!TitanConfiguration::default().override_field(&configuration, "unknown"));
+    }
+
+    #[test]
+    fn extends() {
+        let base = b"(extends: Some(\"root.titan.ron\"), configuration: (format: \"rg8unorm\", sampler: Nearest, max_size: (512, 512)))";
+        let bytes = b"(extends: Some(\"base.titan.ron\"), configuration: (max_size: (1024, 1024)), textures: [])";
+        let mut titan = ron::de::from_bytes::<Titan>(bytes).unwrap();
+        let mut fields = configuration_fields(bytes, TitanSyntax::Ron).unwrap();
+
+        let extends = extend(
+            &mut titan.configuration,
+            &mut fields,
+            "base.titan.ron",
+            base,
+        )
+        .unwrap();
+
+        assert_eq!(extends.as_deref(), Some("root.titan.ron"));
+        assert_eq!(titan.configuration.max_size, UVec2::new(1024, 1024));
+        assert_eq!(titan.configuration.format, TextureFormat::Rg8Unorm);
+        assert!(matches!(titan.configuration.sampler, TitanSampler::Nearest));
+        assert!(fields.contains("format") && fields.contains("max_size"));
+
+        let assets = TempAssets::new("extends");
+        std::fs::write(
+            assets.join("a.titan.ron"),
+            "(extends: Some(\"b.titan.ron\"), textures: [(path: \"a.png\")])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("b.titan.ron"),
+            "(extends: Some(\"a.titan.ron\"))",
+        )
+        .unwrap();
+
+        let result = export_atlas(
+            &assets,
+            Path::new("a.titan.ron"),
+            &assets.join("a.png"),
+            &assets.join("a.layout.ron"),
+        );
+        assert!(matches!(
+            result,
+            Err(ExportError::SpriteSheetLoaderError(
+                SpriteSheetLoaderError::CyclicExtendsError(path)
+            )) if path == "a.titan.ron"
+        ));
+    }
+
+    #[test]
+    fn scale_entry() {
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"a.png\", scale: Some(0.5)), (path: \"a.png\", scale: Some(2.0), scale_filter: Bilinear)])",
+        )
+        .unwrap();
+        validate(&titan).unwrap();
+        let source_images = HashMap::from([("a.png".to_string(), image(UVec2::new(4, 2)))]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+        assert_eq!(packed_atlas.layout.textures[0].size(), UVec2::new(2, 1));
+        assert_eq!(packed_atlas.layout.textures[1].size(), UVec2::new(8, 4));
+
+        /* A red and a blue pixel blend into purple */
+        let mut red_and_blue = image(UVec2::new(2, 1));
+        red_and_blue.data[4..].copy_from_slice(&[0, 0, 255, 255]);
+        let scaled = scale(&red_and_blue, UVec2::ONE, TitanScaleFilter::Bilinear).unwrap();
+        assert_eq!(scaled.data, [128, 0, 128, 255]);
+    }
+
+    #[test]
+    fn json_syntax() {
+        let bytes = br#"{
+            "configuration": {"max_size": [512, 512], "sampler": "Nearest"},
+            "textures": [
+                {"path": "a.png", "sprite_sheet": {"Homogeneous": {"tile_size": [2, 2], "columns": 2, "rows": 1}}},
+                {"path": "b.png", "sprite_sheet": {"Heterogeneous": {"rects": [[[0, 0], [1, 1]], {"name": "b", "position": [1, 1], "size": [1, 1]}]}}}
+            ]
+        }"#;
+        let syntax = TitanSyntax::from_path(Path::new("atlas.titan.json"));
+        assert_eq!(syntax, TitanSyntax::Json);
+
+        let titan = syntax.deserialize::<Titan>(bytes).unwrap();
+        assert_eq!(titan.configuration.max_size, UVec2::new(512, 512));
+        assert!(matches!(
+            titan.textures[0].sprite_sheet,
+            TitanSpriteSheet::Homogeneous { columns: 2, .. }
+        ));
+        let TitanSpriteSheet::Heterogeneous { rects, .. } = &titan.textures[1].sprite_sheet else {
+            panic!("Expected Heterogeneous");
+        };
+        assert_eq!(rects[1].name.as_deref(), Some("b"));
+        assert_eq!(
+            configuration_fields(bytes, syntax).unwrap(),
+            HashSet::from_iter(["max_size".to_string(), "sampler".to_string()])
+        );
+    }
+
+    #[test]
+    fn limits() {
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"a.png\", sprite_sheet: Homogeneous(tile_size: (1, 1), columns: 100000, rows: 100000))])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([("a.png".to_string(), image(UVec2::new(4, 4)))]);
+
+        assert!(matches!(
+            pack_atlas(titan, source_images),
+            Err(SpriteSheetLoaderError::LimitExceeded(
+                _,
+                10_000_000_000,
+                65536
+            ))
+        ));
+    }
+
+    #[test]
+    fn border_padding() {
+        /* 2x2 tiles of size 2 that are flush with the border and have a gap of 2 in between */
+        let size = UVec2::new(6, 6);
+        let expected = [(0, 0), (4, 0), (0, 4), (4, 4)]
+            .map(|(x, y)| URect::from_corners(UVec2::new(x, y), UVec2::new(x + 2, y + 2)));
+        let homogeneous = ron::de::from_str::<TitanSpriteSheet>(
+            "Homogeneous(tile_size: (2, 2), columns: 2, rows: 2, padding: (1, 1), border_padding: Some((0, 0)))",
+        )
+        .unwrap();
+        let grid = ron::de::from_str::<TitanSpriteSheet>(
+            "Grid(tile_size: (2, 2), padding: (1, 1), border_padding: Some((0, 0)))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            sprite_rects(&homogeneous, &image(size), "", 0, false).unwrap(),
+            expected
+        );
+        assert_eq!(
+            sprite_rects(&grid, &image(size), "", 0, false).unwrap(),
+            expected
+        );
+
+        /* Without border padding the padding surrounds every tile */
+        let grid =
+            ron::de::from_str::<TitanSpriteSheet>("Grid(tile_size: (2, 2), padding: (1, 1))")
+                .unwrap();
+        assert!(sprite_rects(&grid, &image(size), "", 0, false).is_err());
+        assert_eq!(
+            sprite_rects(&grid, &image(UVec2::new(8, 8)), "", 0, false).unwrap(),
+            expected.map(|rect| URect::from_corners(rect.min + 1, rect.max + 1))
+        );
+    }
+
+    #[test]
+    fn clamp_rects() {
+        let grid = ron::de::from_str::<TitanSpriteSheet>("Grid(tile_size: (4, 4))").unwrap();
+        let size = UVec2::new(8, 6);
+
+        assert!(sprite_rects(&grid, &image(size), "", 0, false).is_err());
+        assert_eq!(
+            sprite_rects(&grid, &image(size), "", 0, true).unwrap(),
+            vec![
+                URect::new(0, 0, 4, 4),
+                URect::new(4, 0, 8, 4),
+                URect::new(0, 4, 4, 6),
+                URect::new(4, 4, 8, 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn flip() {
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"a.png\", name: Some(\"a\"), anchor: Some((0.0, 0.25)), flip: Some((x: true))), (path: \"a.png\", flip: Some((x: true, y: true, suffix: \"_rotated\")))])",
+        )
+        .unwrap();
+        /* A red pixel on the left and a blue pixel on the right */
+        let mut red_and_blue = image(UVec2::new(2, 1));
+        red_and_blue.data[4..].copy_from_slice(&[0, 0, 255, 255]);
+        let source_images = HashMap::from([("a.png".to_string(), red_and_blue.clone())]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(packed_atlas.layout.len(), 4);
+        assert_eq!(packed_atlas.names["a"], 0);
+        assert_eq!(packed_atlas.names["a_flipped"], 1);
+        assert_eq!(packed_atlas.anchors[1], Vec2::new(1.0, 0.25));
+        let pixel = |index: usize| {
+            let rect = packed_atlas.layout.textures[index];
+            packed_atlas
+                .texture
+                .pixel_bytes(rect.min.extend(0))
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(pixel(0), [255, 0, 0, 255]);
+        assert_eq!(pixel(1), [0, 0, 255, 255]);
+        assert_eq!(pixel(3), [0, 0, 255, 255]);
+        assert_eq!(
+            flip_image(&red_and_blue, true, true).data,
+            rotate_clockwise(&rotate_clockwise(&red_and_blue)).data
+        );
+    }
+
+    #[test]
+    fn extrude_edges() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (always_pack: true, padding: (2, 1), extrude_edges: true), textures: [(path: \"a.png\")])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([("a.png".to_string(), image(UVec2::new(2, 2)))]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(packed_atlas.layout.textures[0], URect::new(0, 0, 2, 2));
+        let pixel = |x: u32, y: u32| {
+            packed_atlas
+                .texture
+                .pixel_bytes(UVec2::new(x, y).extend(0))
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(pixel(3, 1), [255, 0, 0, 255]);
+        assert_eq!(pixel(1, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(3, 2), [255, 0, 0, 255]);
+        assert_eq!(pixel(4, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(0, 3), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn mipmaps() {
+        let mut texture = image(UVec2::new(4, 2));
+        assert!(generate_mipmaps(&mut texture));
+        /* 4x2, 2x1 and 1x1 */
+        assert_eq!(texture.texture_descriptor.mip_level_count, 3);
+        assert_eq!(texture.data.len(), (8 + 2 + 1) * 4);
+        assert!(texture
+            .data
+            .chunks_exact(4)
+            .all(|pixel| pixel == [255, 0, 0, 255]));
+
+        /* White and black average to mid gray in linear space, which is brighter in srgb */
+        let checkerboard = |format: TextureFormat| {
+            Image::new(
+                Extent3d {
+                    width: 2,
+                    height: 2,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                [
+                    [255, 255, 255, 255],
+                    [0, 0, 0, 255],
+                    [255, 255, 255, 0],
+                    [0, 0, 0, 0],
+                ]
+                .concat(),
+                format,
+                RenderAssetUsages::MAIN_WORLD,
+            )
+        };
+        let mut srgb = checkerboard(TextureFormat::Rgba8UnormSrgb);
+        assert!(generate_mipmaps(&mut srgb));
+        assert_eq!(srgb.texture_descriptor.mip_level_count, 2);
+        assert_eq!(srgb.data[16..], [188, 188, 188, 128]);
+
+        let mut unorm = checkerboard(TextureFormat::Rgba8Unorm);
+        assert!(generate_mipmaps(&mut unorm));
+        assert_eq!(unorm.data[16..], [128, 128, 128, 128]);
+    }
+
+    #[test]
+    fn array_output() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (output: Array, generate_mipmaps: true), textures: [(path: \"a.png\", sprite_sheet: Grid(tile_size: (2, 2))), (path: \"b.png\")])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(4, 2))),
+            ("b.png".to_string(), image(UVec2::new(2, 2))),
+        ]);
+
+        let packed_atlas = pack_atlas(titan.clone(), source_images).unwrap();
+
+        assert_eq!(packed_atlas.layout.size, UVec2::new(2, 2));
+        assert_eq!(
+            packed_atlas.layout.textures,
+            vec![URect::new(0, 0, 2, 2); 3]
+        );
+        let descriptor = &packed_atlas.texture.texture_descriptor;
+        assert_eq!(descriptor.size.depth_or_array_layers, 3);
+        assert_eq!(descriptor.mip_level_count, 2);
+        /* Every layer is followed by its own mip chain */
+        assert_eq!(packed_atlas.texture.data.len(), 3 * (4 + 1) * 4);
+        assert!(packed_atlas
+            .texture
+            .data
+            .chunks_exact(4)
+            .all(|pixel| pixel == [255, 0, 0, 255]));
+
+        /* Exported layers are stacked from top to bottom, without their mip chains */
+        let assets = TempAssets::new("array_export");
+        for (name, width) in [("a", 4), ("b", 2)] {
+            positional_image(UVec2::new(width, 2))
+                .try_into_dynamic()
+                .unwrap()
+                .save(assets.join(format!("{name}.png")))
+                .unwrap();
+        }
+        export_titan(
+            titan.clone(),
+            &assets,
+            &assets.join("atlas.png"),
+            &assets.join("atlas.layout.ron"),
+        )
+        .unwrap();
+        let atlas = image::open(assets.join("atlas.png")).unwrap().into_rgba8();
+        assert_eq!(atlas.dimensions(), (2, 6));
+        assert_eq!(atlas.get_pixel(1, 0).0, [1, 0, 0, 255]);
+        assert_eq!(atlas.get_pixel(0, 2).0, [2, 0, 0, 255]);
+        assert_eq!(atlas.get_pixel(1, 5).0, [1, 1, 0, 255]);
+
+        let source_images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(4, 2))),
+            ("b.png".to_string(), image(UVec2::new(3, 2))),
+        ]);
+        assert!(matches!(
+            pack_atlas(titan, source_images),
+            Err(SpriteSheetLoaderError::ArraySizeError(2, _, _))
+        ));
+    }
+
+    #[test]
+    fn sort_order() {
+        let manifest = |sort_order: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (sort_order: {sort_order}), textures: [(path: \"a.png\", name: Some(\"b\")), (path: \"a.png\", name: Some(\"a\")), (path: \"a.png\")])"
+            ))
+            .unwrap()
+        };
+        let source_images = HashMap::from([("a.png".to_string(), image(UVec2::new(2, 2)))]);
+        let min = |sort_order: &str| {
+            let packed_atlas = pack_atlas(manifest(sort_order), source_images.clone()).unwrap();
+            assert_eq!(packed_atlas.names["a"], 1);
+            packed_atlas
+                .layout
+                .textures
+                .iter()
+                .map(|rect| rect.min)
+                .collect::<Vec<_>>()
+        };
+
+        let as_declared = min("AsDeclared");
+        let by_name = min("ByName");
+        assert_eq!(by_name[0], as_declared[1]);
+        assert_eq!(by_name[1], as_declared[0]);
+        assert_eq!(by_name[2], as_declared[2]);
+        assert_eq!(min("BySizeDesc"), as_declared);
+    }
+
+    #[test]
+    fn grid_out_of_bounds() {
+        let homogeneous = ron::de::from_str::<TitanSpriteSheet>(
+            "Homogeneous(tile_size: (2, 2), columns: 3, rows: 1, padding: (1, 1), offset: (1, 0))",
+        )
+        .unwrap();
+
+        assert!(sprite_rects(&homogeneous, &image(UVec2::new(12, 3)), "a.png", 0, false).is_ok());
+        assert!(matches!(
+            sprite_rects(&homogeneous, &image(UVec2::new(11, 4)), "a.png", 0, false),
+            Err(SpriteSheetLoaderError::GridOutOfBounds {
+                required_size: UVec2 { x: 12, y: 3 },
+                ..
+            })
+        ));
+        assert!(sprite_rects(&homogeneous, &image(UVec2::new(11, 4)), "a.png", 0, true).is_ok());
+    }
+
+    #[test]
+    fn r8_output() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (format: \"r8unorm\", padding: (1, 1), trim: true, extrude_edges: true, generate_mipmaps: true), textures: [(path: \"a.png\", sprite_sheet: Grid(tile_size: (2, 2))), (path: \"b.png\")])",
+        )
+        .unwrap();
+        let r8 = |size: UVec2, data: &[u8]| {
+            Image::new(
+                Extent3d {
+                    width: size.x,
+                    height: size.y,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                data.to_vec(),
+                TextureFormat::R8Unorm,
+                RenderAssetUsages::MAIN_WORLD,
+            )
+        };
+        let source_images = HashMap::from([
+            (
+                "a.png".to_string(),
+                r8(UVec2::new(4, 2), &[10, 20, 30, 40, 50, 60, 70, 80]),
+            ),
+            ("b.png".to_string(), r8(UVec2::new(1, 1), &[90])),
+        ]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(
+            packed_atlas.texture.texture_descriptor.format,
+            TextureFormat::R8Unorm
+        );
+        assert_eq!(packed_atlas.layout.len(), 3);
+        let pixel = |index: usize, offset: UVec2| {
+            let rect = packed_atlas.layout.textures[index];
+            packed_atlas
+                .texture
+                .pixel_bytes((rect.min + offset).extend(0))
+                .unwrap()
+                .to_vec()
+        };
+        assert_eq!(pixel(0, UVec2::new(1, 1)), [60]);
+        assert_eq!(pixel(1, UVec2::new(0, 0)), [30]);
+        assert_eq!(pixel(2, UVec2::new(0, 0)), [90]);
+        /* Extruded into the padding */
+        assert_eq!(pixel(2, UVec2::new(1, 1)), [90]);
+
+        /* Grayscale pngs are not expanded to RGBA on their way */
+        let assets = TempAssets::new("r8");
+        image::GrayImage::from_raw(2, 1, vec![10, 20])
+            .unwrap()
+            .save(assets.join("a.png"))
+            .unwrap();
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (format: \"r8unorm\", on_format_mismatch: Error), textures: [(path: \"a.png\")])",
+        )
+        .unwrap();
+        let result = export_titan(
+            titan,
+            &assets,
+            &assets.join("atlas.png"),
+            &assets.join("atlas.layout.ron"),
+        )
+        .map(|_| image::open(assets.join("atlas.png")).unwrap());
+        let atlas = result.unwrap();
+        assert_eq!(atlas.color(), image::ColorType::L8);
+        assert_eq!(atlas.as_bytes()[..2], [10, 20]);
+    }
+
+    #[test]
+    fn tileable() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (trim: true, padding: (3, 0), extrude_edges: true), textures: [(path: \"a.png\", tileable: true), (path: \"a.png\")])",
+        )
+        .unwrap();
+        /* A red pixel, a blue pixel and a transparent pixel */
+        let mut source_image = image(UVec2::new(3, 1));
+        source_image.data[4..].copy_from_slice(&[0, 0, 255, 255, 0, 0, 0, 0]);
+        let source_images = HashMap::from([("a.png".to_string(), source_image)]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(packed_atlas.tileables, vec![true, false]);
+        assert_eq!(packed_atlas.layout.textures[0].size(), UVec2::new(3, 1));
+        assert_eq!(packed_atlas.layout.textures[1].size(), UVec2::new(2, 1));
+        let padding = |index: usize| {
+            let rect = packed_atlas.layout.textures[index];
+            (0..3)
+                .map(|x| {
+                    packed_atlas
+                        .texture
+                        .pixel_bytes(UVec2::new(rect.max.x + x, rect.min.y).extend(0))
+                        .unwrap()
+                        .to_vec()
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            padding(0),
+            [vec![255, 0, 0, 255], vec![0, 0, 255, 255], vec![0, 0, 0, 0]]
+        );
+        assert_eq!(padding(1), vec![vec![0, 0, 255, 255]; 3]);
+    }
+
+    #[test]
+    fn unsupported_image_format() {
+        assert!(image_format("a.png").is_ok());
+        #[cfg(not(feature = "webp"))]
+        assert!(matches!(
+            image_format("a.webp"),
+            Err(SpriteSheetLoaderError::UnsupportedImageFormat { ext, .. }) if ext == "webp"
+        ));
+        assert!(matches!(
+            image_format("a.txt"),
+            Err(SpriteSheetLoaderError::NotAnImageError(_, _))
+        ));
+        assert_eq!(
+            image_format("assets/a").unwrap_err().to_string(),
+            "Loading from assets/a does not provide Image, because it has no file extension"
+        );
+    }
+
+    #[test]
+    fn labels() {
+        let settings = SpriteSheetLoaderSettings::default();
+        let labels = settings.labels();
+        assert_eq!((labels.texture, labels.layout), ("texture", "layout"));
+
+        let settings = SpriteSheetLoaderSettings {
+            texture_label: Some("hero_texture".to_string()),
+            ..Default::default()
+        };
+        let labels = settings.labels();
+        assert_eq!((labels.texture, labels.layout), ("hero_texture", "layout"));
+    }
+
+    #[test]
+    fn multiple_pages() {
+        let manifest = |allow_multiple_pages: bool| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (initial_size: (8, 8), max_size: (8, 8), allow_multiple_pages: {allow_multiple_pages}), textures: [(path: \"a.png\"), (path: \"b.png\"), (path: \"a.png\"), (path: \"a.png\")])"
+            ))
+            .unwrap()
+        };
+        let source_images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(8, 4))),
+            ("b.png".to_string(), image(UVec2::new(8, 8))),
+        ]);
+
+        assert!(matches!(
+            pack_atlas(manifest(false), source_images.clone()),
+            Err(SpriteSheetLoaderError::NotEnoughSpaceError(_))
+        ));
+
+        let packed_atlas = pack_atlas(manifest(true), source_images).unwrap();
+        assert_eq!(packed_atlas.additional_pages.len(), 2);
+        assert_eq!(packed_atlas.page_indices, vec![0, 1, 2, 2]);
+        assert_eq!(packed_atlas.layout.textures[1], URect::new(0, 0, 8, 8));
+        assert_ne!(
+            packed_atlas.layout.textures[2],
+            packed_atlas.layout.textures[3]
+        );
+    }
+
+    #[test]
+    fn checksum() {
+        let assets = TempAssets::new("checksum");
+        let manifest = "(version: Some(\"1.2\"), extends: Some(\"base.titan.ron\"), textures: [(path: \"a.png\")])";
+        std::fs::write(assets.join("a.titan.ron"), manifest).unwrap();
+        std::fs::write(
+            assets.join("base.titan.ron"),
+            "(configuration: (padding: (1, 1)))",
+        )
+        .unwrap();
+        std::fs::write(assets.join("a.png"), [1, 2, 3]).unwrap();
+        let checksum = || crate::export::checksum(&assets, Path::new("a.titan.ron")).unwrap();
+
+        let initial = checksum();
+        let unchanged = checksum();
+        std::fs::write(
+            assets.join("base.titan.ron"),
+            "(configuration: (padding: (2, 2)))",
+        )
+        .unwrap();
+        let base_changed = checksum();
+        std::fs::write(assets.join("a.png"), [1, 2, 4]).unwrap();
+        let image_changed = checksum();
+
+        assert_eq!(initial, unchanged);
+        assert_ne!(initial, base_changed);
+        assert_ne!(base_changed, image_changed);
+
+        std::fs::write(
+            assets.join("glob.titan.ron"),
+            "(textures: [(path: \"a.png\"), (glob: Some(\"*.png\"))])",
+        )
+        .unwrap();
+        assert!(matches!(
+            crate::export::checksum(&assets, Path::new("glob.titan.ron")),
+            Err(ExportError::UnsupportedEntryError(1))
+        ));
+        assert_eq!(
+            ron::de::from_str::<Titan>(manifest)
+                .unwrap()
+                .version
+                .as_deref(),
+            Some("1.2")
+        );
+    }
+
+    #[test]
+    fn color_space() {
+        let titan = |color_space: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (format: \"rgba8unorm\", on_format_mismatch: Error), textures: [(path: \"a.png\", color_space: Some({color_space}))])"
+            ))
+            .unwrap()
+        };
+        let source_images = || HashMap::from([("a.png".to_string(), image(UVec2::new(2, 2)))]);
+
+        assert!(matches!(
+            validate(&titan("Srgb")),
+            Err(SpriteSheetLoaderError::ColorSpaceMismatch {
+                index: 0,
+                color_space: TitanColorSpace::Srgb,
+                format: TextureFormat::Rgba8Unorm,
+            })
+        ));
+
+        /* The sRGB source is reinterpreted as linear instead of failing the format check */
+        let titan = titan("Linear");
+        validate(&titan).unwrap();
+        let packed_atlas = pack_atlas(titan, source_images()).unwrap();
+        assert_eq!(
+            packed_atlas.texture.texture_descriptor.format,
+            TextureFormat::Rgba8Unorm
+        );
+        assert_eq!(packed_atlas.texture.data[..4], [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn sources() {
+        let mut titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"a.png\", sprite_sheet: Grid(tile_size: (1, 1))), (path: \"b.png\", flip: Some((x: true))), (path: \"a.png\"), (data: Some(Inline(\"/wAA/w==\", \"rgba8unorm-srgb\", (1, 1)))), (path: \"b.png\")])",
+        )
+        .unwrap();
+        let mut source_images = decode_inline_images(&mut titan.textures).unwrap();
+        source_images.insert("a.png".to_string(), image(UVec2::new(2, 1)));
+        source_images.insert("b.png".to_string(), image(UVec2::new(1, 1)));
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(packed_atlas.layout.len(), 7);
+        let sources = TextureAtlasSources(packed_atlas.sources);
+        assert_eq!(sources["a.png"], vec![0..2, 4..5]);
+        assert_eq!(sources["b.png"], vec![2..4, 6..7]);
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources.indices("b.png").collect::<Vec<_>>(), [2, 3, 6]);
+        assert_eq!(sources.indices("c.png").count(), 0);
+    }
+
+    #[test]
+    fn ignore_missing() {
+        let assets = TempAssets::new("missing");
+        image::RgbaImage::new(2, 1)
+            .save(assets.join("a.png"))
+            .unwrap();
+        let export = |ignore_missing: bool, textures: &str| {
+            let titan = ron::de::from_str::<Titan>(&format!(
+                "(configuration: (ignore_missing: {ignore_missing}), textures: {textures})"
+            ))
+            .unwrap();
+            export_titan(
+                titan,
+                &assets,
+                &assets.join("atlas.png"),
+                &assets.join("atlas.layout.ron"),
+            )
+            .map(|_| std::fs::read_to_string(assets.join("atlas.layout.ron")).unwrap())
+        };
+
+        let strict = export(false, "[(path: \"missing.png\"), (path: \"a.png\")]");
+        let skipped = export(true, "[(path: \"missing.png\"), (path: \"a.png\")]");
+        let all_missing = export(true, "[(path: \"missing.png\")]");
+
+        assert!(matches!(strict, Err(ExportError::IoError(..))));
+        assert_eq!(skipped.unwrap().matches("(2, 1)").count(), 1);
+        assert!(matches!(
+            all_missing,
+            Err(ExportError::SpriteSheetLoaderError(
+                SpriteSheetLoaderError::NoEntriesError
+            ))
+        ));
+    }
+
+    #[test]
+    fn animation_playback() {
+        let titan_animations =
+            |animations: &str| ron::de::from_str::<Vec<TitanAnimation>>(animations).unwrap();
+        let durations = [None, Some(Duration::from_millis(50)), None];
+        let names = HashMap::from([("jump".to_string(), 1), ("land".to_string(), 2)]);
+
+        let animations = build_animations(
+            titan_animations(
+                "[(name: \"bounce\", frames: [0, \"jump\", \"land\"], frame_rate: Some(10.0), playback: PingPong), (name: \"die\", frames: [2], playback: Once), (name: \"idle\", frames: [0])]",
+            ),
+            &durations,
+            &names,
+        )
+        .unwrap();
+
+        let frames = |name: &str| {
+            animations[name]
+                .iter()
+                .map(|frame| (frame.index, frame.duration.as_millis()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(frames("bounce"), [(0, 100), (1, 50), (2, 100), (1, 50)]);
+        assert_eq!(animations["bounce"].playback, AnimationPlayback::PingPong);
+        assert_eq!(animations["die"].playback, AnimationPlayback::Once);
+        assert_eq!(frames("idle"), [(0, 0)]);
+        assert_eq!(animations["idle"].playback, AnimationPlayback::Forward);
+        assert_eq!(ping_pong(vec![3, 4]), [3, 4]);
+
+        assert!(matches!(
+            build_animations(
+                titan_animations("[(name: \"a\", frames: [0], frame_rate: Some(0.0))]"),
+                &durations,
+                &names,
+            ),
+            Err(SpriteSheetLoaderError::InvalidFrameRateError(_, name)) if name == "a"
+        ));
+        assert!(matches!(
+            build_animations(
+                titan_animations("[(name: \"a\", frames: [\"jump\", \"fall\"])]"),
+                &durations,
+                &names,
+            ),
+            Err(SpriteSheetLoaderError::UnknownAnimationFrameError(animation, frame)) if animation == "a" && frame == "fall"
+        ));
+    }
+
+    #[test]
+    fn color_grid() {
+        /* Cells of 2 and 3 columns and of 1 and 2 rows, separated by a magenta gutter */
+        let mut source_image = image(UVec2::new(6, 4));
+        for (i, pixel) in source_image.data.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % 6, i / 6);
+            if x == 2 || y == 1 {
+                pixel.copy_from_slice(&[255, 0, 255, 255]);
+            }
+        }
+        source_image.data[8..12].copy_from_slice(&[254, 0, 255, 255]);
+        let titan = |tolerance: u8| {
+            ron::de::from_str::<Titan>(&format!(
+                "(textures: [(path: \"a.png\", sprite_sheet: ColorGrid(gutter_color: (255, 0, 255), tolerance: {tolerance}))])"
+            ))
+            .unwrap()
+        };
+        let source_images = || HashMap::from([("a.png".to_string(), source_image.clone())]);
+
+        let packed_atlas = pack_atlas(titan(1), source_images()).unwrap();
+        assert_eq!(
+            packed_atlas
+                .source_rects
+                .iter()
+                .map(|source| source.rect)
+                .collect::<Vec<_>>(),
+            [
+                URect::new(0, 0, 2, 1),
+                URect::new(3, 0, 6, 1),
+                URect::new(0, 2, 2, 4),
+                URect::new(3, 2, 6, 4),
+            ]
+        );
+
+        /* Without tolerance the off-color pixel breaks up the gutter column */
+        let packed_atlas = pack_atlas(titan(0), source_images()).unwrap();
+        assert_eq!(packed_atlas.layout.len(), 2);
+    }
+
+    #[test]
+    fn build_atlas_resolves_images() {
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"a.png\"), (path: \"b.png\"), (path: \"a.png\"), (data: Some(Inline(\"/wAA/w==\", \"rgba8unorm-srgb\", (1, 1))))])",
+        )
+        .unwrap();
+        let mut resolved = Vec::new();
+
+        let (layout, texture) = build_atlas(titan, |path| {
+            resolved.push(path.to_string());
+            image(UVec2::new(2, 3))
+        })
+        .unwrap();
+
+        assert_eq!(resolved, ["a.png", "b.png"]);
+        assert_eq!(layout.len(), 4);
+        assert_eq!(layout.textures[3].size(), UVec2::ONE);
+        assert_eq!(layout.size, texture.size());
+
+        /* Sprites of dropped pages would point outside of the texture */
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (initial_size: (4, 4), max_size: (4, 4), padding: (0, 0), allow_multiple_pages: true), textures: [(path: \"a.png\"), (path: \"b.png\")])",
+        )
+        .unwrap();
+        assert!(build_atlas(titan, |_| image(UVec2::new(4, 4))).is_err());
+    }
+
+    #[test]
+    fn alignment() {
+        let titan = |alignment: u32| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (initial_size: (10, 10), padding: (1, 1), alignment: {alignment}), textures: [(path: \"a.png\"), (path: \"b.png\"), (path: \"c.png\"), (path: \"d.png\")])"
+            ))
+            .unwrap()
+        };
+        let sizes = [
+            UVec2::new(3, 5),
+            UVec2::ONE,
+            UVec2::new(6, 2),
+            UVec2::new(2, 7),
+        ];
+        let source_images = ["a.png", "b.png", "c.png", "d.png"]
+            .into_iter()
+            .zip(sizes)
+            .map(|(path, size)| (path.to_string(), image(size)))
+            .collect::<HashMap<_, _>>();
+
+        let packed_atlas = pack_atlas(titan(4), source_images).unwrap();
+
+        assert_eq!(packed_atlas.layout.size % 4, UVec2::ZERO);
+        assert_eq!(packed_atlas.layout.size, packed_atlas.texture.size());
+        for (rect, size) in packed_atlas.layout.textures.iter().zip(sizes) {
+            assert_eq!(rect.min % 4, UVec2::ZERO);
+            assert_eq!(rect.size(), size);
+        }
+        assert!(matches!(
+            validate(&titan(0)),
+            Err(SpriteSheetLoaderError::ZeroAlignmentError)
+        ));
+    }
+
+    #[test]
+    fn trim_render_offset() {
+        let trim = SpriteTrim {
+            original_size: UVec2::new(10, 10),
+            offset: UVec2::new(2, 4),
+        };
+        let trimmed_size = UVec2::new(4, 2);
+
+        assert_eq!(trim.render_offset(trimmed_size), Vec2::new(-1.0, 0.0));
+        assert_eq!(
+            trim.anchor(Vec2::splat(0.5), trimmed_size).as_vec(),
+            Vec2::new(0.25, 0.0)
+        );
+        assert_eq!(
+            trim.anchor(Vec2::new(0.2, 0.4), trimmed_size).as_vec(),
+            Vec2::new(-0.5, 0.5)
+        );
+
+        let untrimmed = SpriteTrim {
+            original_size: trimmed_size,
+            offset: UVec2::ZERO,
+        };
+        assert_eq!(untrimmed.render_offset(trimmed_size), Vec2::ZERO);
+        assert_eq!(
+            untrimmed.anchor(Vec2::splat(0.5), trimmed_size).as_vec(),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn name_prefix() {
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"strip.png\", name_prefix: Some(\"frame\"), sprite_sheet: Grid(tile_size: (1, 1)), flip: Some((x: true))), (path: \"strip.png\", name_prefix: Some(\"single\"))])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([("strip.png".to_string(), image(UVec2::new(3, 1)))]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        let mut names = packed_atlas.names.into_iter().collect::<Vec<_>>();
+        names.sort_by_key(|(_, index)| *index);
+        assert_eq!(
+            names,
+            [
+                ("frame0".to_string(), 0),
+                ("frame1".to_string(), 1),
+                ("frame2".to_string(), 2),
+                ("frame0_flipped".to_string(), 3),
+                ("frame1_flipped".to_string(), 4),
+                ("frame2_flipped".to_string(), 5),
+                ("single0".to_string(), 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn allow_empty() {
+        let titan = |allow_empty: bool| {
+            ron::de::from_str::<Titan>(&format!(
+                "(version: Some(\"1\"), configuration: (allow_empty: {allow_empty}, format: \"rgba8unorm\"), textures: [])"
+            ))
+            .unwrap()
+        };
+
+        assert!(matches!(
+            validate(&titan(false)),
+            Err(SpriteSheetLoaderError::NoEntriesError)
+        ));
+
+        let (layout, texture) = build_atlas(titan(true), |_| unreachable!()).unwrap();
+        assert_eq!(layout.len(), 0);
+        assert_eq!(layout.size, UVec2::ONE);
+        assert_eq!(texture.size(), UVec2::ONE);
+        assert_eq!(texture.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+        assert_eq!(texture.data, [0; 4]);
+
+        let mut titan = titan(true);
+        titan.configuration.format = TextureFormat::Bc1RgbaUnorm;
+        titan.configuration.default_index = Some(0);
+        let packed_atlas = pack_atlas(titan, HashMap::new()).unwrap();
+        assert_eq!(packed_atlas.texture.size(), UVec2::splat(4));
+        assert_eq!(packed_atlas.texture.data, [0; 8]);
+        assert_eq!(packed_atlas.layout.size, UVec2::splat(4));
+        assert_eq!(packed_atlas.default_index, Some(0));
+    }
+
+    #[test]
+    fn render_asset_usages() {
+        let titan = |render_asset_usages: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (render_asset_usages: (\"{render_asset_usages}\")), textures: [(path: \"a.png\")])"
+            ))
+            .unwrap()
+        };
+        let usage = |titan: Titan| {
+            build_atlas(titan, |_| image(UVec2::ONE))
+                .unwrap()
+                .1
+                .asset_usage
+        };
+
+        assert_eq!(
+            usage(ron::de::from_str("(textures: [(path: \"a.png\")])").unwrap()),
+            RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD
+        );
+        assert_eq!(
+            usage(titan("RENDER_WORLD")),
+            RenderAssetUsages::RENDER_WORLD
+        );
+        assert_eq!(
+            usage(titan("MAIN_WORLD | RENDER_WORLD")),
+            RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD
+        );
+    }
+
+    #[test]
+    fn default_index() {
+        let titan = |default_index: usize| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (default_index: Some({default_index})), textures: [(path: \"strip.png\", sprite_sheet: Grid(tile_size: (1, 1)))])"
+            ))
+            .unwrap()
+        };
+        let source_images = || HashMap::from([("strip.png".to_string(), image(UVec2::new(3, 1)))]);
+
+        let packed_atlas = pack_atlas(titan(1), source_images()).unwrap();
+        assert_eq!(packed_atlas.default_index, Some(1));
+        assert!(matches!(
+            pack_atlas(titan(3), source_images()),
+            Err(SpriteSheetLoaderError::InvalidDefaultIndexError(3, 3))
+        ));
+
+        let mut texture_atlas = TextureAtlas {
+            texture: Handle::default(),
+            layout: Handle::default(),
+            frame_count: 3,
+            atlas_size: UVec2::new(3, 1),
+            names: Handle::default(),
+            animations: Handle::default(),
+            pages: Vec::new(),
+            page_indices: Vec::new(),
+            version: None,
+            checksum: 0,
+            default_index: Some(1),
+        };
+        assert_eq!(texture_atlas.clamp_index(2), 2);
+        assert_eq!(texture_atlas.clamp_index(3), 1);
+        texture_atlas.default_index = None;
+        assert_eq!(texture_atlas.clamp_index(7), 2);
+        texture_atlas.frame_count = 0;
+        assert_eq!(texture_atlas.clamp_index(7), 0);
+        /* The default index of a smaller or empty texture atlas is out of range as well */
+        texture_atlas.default_index = Some(1);
+        assert_eq!(texture_atlas.clamp_index(7), 0);
+        texture_atlas.frame_count = 1;
+        assert_eq!(texture_atlas.clamp_index(7), 0);
+    }
+
+    #[test]
+    fn source_rect() {
+        let sheet = positional_image(UVec2::new(8, 6));
+        let titan = |source_rect: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(textures: [(path: \"sheet.png\", source_rect: Some({source_rect}), sprite_sheet: Homogeneous(tile_size: (2, 2), columns: 2, rows: 1))])"
+            ))
+            .unwrap()
+        };
+        let source_images = || HashMap::from([("sheet.png".to_string(), sheet.clone())]);
+
+        let packed_atlas =
+            pack_atlas(titan("(min: (3, 2), max: (7, 4))"), source_images()).unwrap();
+
+        for (rect, position) in packed_atlas
+            .layout
+            .textures
+            .iter()
+            .zip([UVec2::new(3, 2), UVec2::new(5, 2)])
+        {
+            let pixel = packed_atlas
+                .texture
+                .pixel_bytes(rect.min.extend(0))
+                .unwrap();
+            assert_eq!(pixel, [position.x as u8, position.y as u8, 0, 255]);
+        }
+        assert_eq!(
+            packed_atlas
+                .source_rects
+                .iter()
+                .map(|source| source.rect)
+                .collect::<Vec<_>>(),
+            [URect::new(3, 2, 5, 4), URect::new(5, 2, 7, 4)]
+        );
+        assert!(matches!(
+            pack_atlas(titan("(min: (6, 2), max: (10, 4))"), source_images()),
+            Err(SpriteSheetLoaderError::InvalidRectError(_))
+        ));
+        assert!(matches!(
+            validate(&titan("(min: (3, 2), max: (3, 4))")),
+            Err(SpriteSheetLoaderError::InvalidRectError(_))
+        ));
+    }
+
+    #[test]
+    fn stats() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (initial_size: (8, 8), max_size: (8, 8), padding: (0, 0)), textures: [(path: \"a.png\"), (path: \"b.png\")])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(4, 4))),
+            ("b.png".to_string(), image(UVec2::new(2, 4))),
+        ]);
+
+        let stats = pack_atlas(titan, source_images).unwrap().stats();
+
+        assert_eq!(stats.used_area, 24);
+        assert_eq!(stats.atlas_area, 64);
+        assert_eq!(stats.page_count, 1);
+        assert_eq!(stats.efficiency, 0.375);
+        assert_eq!(stats.wasted_area(), 40);
+
+        /* Both pages have a sprite at the same rect */
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (initial_size: (4, 4), max_size: (4, 4), padding: (0, 0), allow_multiple_pages: true), textures: [(path: \"a.png\"), (path: \"b.png\")])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(4, 4))),
+            ("b.png".to_string(), image(UVec2::new(4, 4))),
+        ]);
+
+        let stats = pack_atlas(titan, source_images).unwrap().stats();
+
+        assert_eq!(stats.page_count, 2);
+        assert_eq!(stats.used_area, 32);
+        assert_eq!(stats.atlas_area, 32);
+        assert_eq!(stats.efficiency, 1.0);
+
+        /* Every layer of an array texture is used on its own */
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (output: Array), textures: [(path: \"a.png\"), (path: \"b.png\"), (path: \"c.png\")])",
+        )
+        .unwrap();
+        let source_images = ["a.png", "b.png", "c.png"]
+            .map(|path| (path.to_string(), image(UVec2::new(2, 2))))
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        let stats = pack_atlas(titan, source_images).unwrap().stats();
+
+        assert_eq!(stats.page_count, 1);
+        assert_eq!(stats.used_area, 12);
+        assert_eq!(stats.atlas_area, 12);
+        assert_eq!(stats.efficiency, 1.0);
+    }
+
+    #[test]
+    fn from_atlas() {
+        let (mut app, assets) = asset_app("from_atlas", false);
+        image::RgbaImage::from_fn(4, 2, |x, y| image::Rgba([x as u8, y as u8, 0, 255]))
+            .save(assets.join("sheet.png"))
+            .unwrap();
+        image::RgbaImage::new(2, 2)
+            .save(assets.join("c.png"))
+            .unwrap();
+        std::fs::write(
+            assets.join("base.titan.ron"),
+            "(textures: [(path: \"sheet.png\", name: Some(\"tile\"), sprite_sheet: Homogeneous(tile_size: (2, 2), columns: 2, rows: 1)), (path: \"c.png\", name: Some(\"c\"), flip: Some((x: true, suffix: \"_flipped\")))])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("screen.titan.ron"),
+            "(configuration: (always_pack: false), textures: [(from_atlas: Some(\"base.titan.ron\"), frames: [\"tile_1\"]), (path: \"c.png\", name: Some(\"c\"))])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("unknown.titan.ron"),
+            "(textures: [(from_atlas: Some(\"base.titan.ron\"), frames: [\"tile_2\"])])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("flipped.titan.ron"),
+            "(textures: [(from_atlas: Some(\"base.titan.ron\"), frames: [\"c_flipped\"])])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("cycle_a.titan.ron"),
+            "(textures: [(from_atlas: Some(\"cycle_b.titan.ron\"), frames: [\"c\"])])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("cycle_b.titan.ron"),
+            "(textures: [(from_atlas: Some(\"cycle_a.titan.ron\"), frames: [\"c\"])])",
+        )
+        .unwrap();
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle = asset_server.load::<TextureAtlas>("screen.titan.ron");
+        let source_rects_handle =
+            asset_server.load::<TextureAtlasSourceRects>("screen.titan.ron#source_rects");
+        let failing_handles = ["unknown", "flipped", "cycle_a"]
+            .map(|name| asset_server.load::<TextureAtlas>(format!("{name}.titan.ron")));
+        let start = Instant::now();
+        while !(asset_server.is_loaded_with_dependencies(&handle)
+            && failing_handles
+                .iter()
+                .all(|handle| asset_server.load_state(handle).is_failed()))
+        {
+            assert!(start.elapsed() < Duration::from_secs(30), "Timed out");
+            assert!(!asset_server.load_state(&handle).is_failed());
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let texture_atlas = app
+            .world()
+            .resource::<Assets<TextureAtlas>>()
+            .get(&handle)
+            .unwrap();
+        let names = app
+            .world()
+            .resource::<Assets<TextureAtlasNames>>()
+            .get(&texture_atlas.names)
+            .unwrap();
+        assert_eq!(names.len(), 2);
+        let source_rects = app
+            .world()
+            .resource::<Assets<TextureAtlasSourceRects>>()
+            .get(&source_rects_handle)
+            .unwrap();
+        assert_eq!(
+            source_rects[names["tile_1"]],
+            SpriteSource {
+                path: "sheet.png".to_string(),
+                rect: URect::new(2, 0, 4, 2),
+                transformed: false,
+            }
+        );
+        let layout = app
+            .world()
+            .resource::<Assets<TextureAtlasLayout>>()
+            .get(&texture_atlas.layout)
+            .unwrap();
+        let texture = app
+            .world()
+            .resource::<Assets<Image>>()
+            .get(&texture_atlas.texture)
+            .unwrap();
+        let rect = layout.textures[names["tile_1"]];
+        assert_eq!(
+            texture.pixel_bytes(rect.min.extend(0)).unwrap(),
+            [2, 0, 0, 255]
+        );
+    }
+
+    #[test]
+    fn heterogeneous_subpixel() {
+        let sheet = positional_image(UVec2::new(4, 1));
+        let titan = |rect: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(textures: [(path: \"sheet.png\", sprite_sheet: HeterogeneousSubpixel(rects: [{rect}]))])"
+            ))
+            .unwrap()
+        };
+
+        let packed_atlas = pack_atlas(
+            titan("(name: Some(\"half\"), position: (0.5, 0.0), size: (2.0, 1.0))"),
+            HashMap::from([("sheet.png".to_string(), sheet)]),
+        )
+        .unwrap();
+
+        let rect = packed_atlas.layout.textures[packed_atlas.names["half"]];
+        assert_eq!(rect.size(), UVec2::new(2, 1));
+        let pixel = |x: u32| {
+            packed_atlas
+                .texture
+                .pixel_bytes((rect.min + UVec2::new(x, 0)).extend(0))
+                .unwrap()
+                .to_vec()
+        };
+        /* Halfway between two pixels */
+        assert_eq!(pixel(0), [1, 0, 0, 255]);
+        assert_eq!(pixel(1), [2, 0, 0, 255]);
+        assert_eq!(packed_atlas.source_rects[0].rect, URect::new(0, 0, 3, 1));
+        assert!(matches!(
+            validate(&titan("(position: (0.5, 0.0), size: (0.0, 1.0))")),
+            Err(SpriteSheetLoaderError::InvalidSubpixelRectError(..))
+        ));
+
+        let float_sheet = Image::new_fill(
+            Extent3d {
+                width: 4,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0; 8],
+            TextureFormat::Rgba16Float,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        assert!(matches!(
+            pack_atlas(
+                titan("(position: (0.5, 0.0), size: (2.0, 1.0))"),
+                HashMap::from([("sheet.png".to_string(), float_sheet)]),
+            ),
+            Err(SpriteSheetLoaderError::SubpixelFormatError(path, TextureFormat::Rgba16Float)) if path == "sheet.png"
+        ));
+    }
+
+    #[test]
+    fn layout_only() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (initial_size: (4, 4), max_size: (4, 4), allow_multiple_pages: true), textures: [(path: \"a.png\"), (path: \"a.png\")])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([("a.png".to_string(), image(UVec2::new(4, 4)))]);
+        let mut packed_atlas = pack_atlas(titan, source_images).unwrap();
+        let layout = packed_atlas.layout.textures.clone();
+
+        packed_atlas.drop_pixels();
+
+        assert!(packed_atlas.texture.data.is_empty());
+        assert_eq!(packed_atlas.texture.size(), UVec2::new(4, 4));
+        assert_eq!(packed_atlas.additional_pages.len(), 1);
+        assert!(packed_atlas.additional_pages[0].data.is_empty());
+        assert_eq!(packed_atlas.layout.textures, layout);
+    }
+
+    #[test]
+    fn grid_shorthand() {
+        let ron_titan = TitanSyntax::Ron
+            .deserialize::<Titan>(b"(textures: Grid(path: \"strip.png\", tile_size: (1, 1)))")
+            .unwrap();
+        let json_titan = TitanSyntax::Json
+            .deserialize::<Titan>(
+                br#"{"textures": {"path": "strip.png", "tile_size": [1, 1], "offset": [1, 0]}}"#,
+            )
+            .unwrap();
+        let source_images = || HashMap::from([("strip.png".to_string(), image(UVec2::new(3, 1)))]);
+
+        assert_eq!(ron_titan.textures.len(), 1);
+        assert_eq!(ron_titan.textures[0].path, "strip.png");
+        assert_eq!(
+            pack_atlas(ron_titan, source_images()).unwrap().layout.len(),
+            3
+        );
+        assert_eq!(
+            pack_atlas(json_titan, source_images())
+                .unwrap()
+                .layout
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn bottom_left_origin() {
+        let sheet = positional_image(UVec2::new(2, 4));
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (coordinate_origin: BottomLeft), textures: [(path: \"sheet.png\", sprite_sheet: Heterogeneous((rects: [((0, 0), (2, 1))], offset: (0, 1)))), (path: \"sheet.png\", sprite_sheet: Grid(tile_size: (2, 2))), (path: \"sheet.png\", source_rect: Some((min: (0, 3), max: (2, 4))))])",
+        )
+        .unwrap();
+
+        let packed_atlas =
+            pack_atlas(titan, HashMap::from([("sheet.png".to_string(), sheet)])).unwrap();
+
+        assert_eq!(
+            packed_atlas
+                .source_rects
+                .iter()
+                .map(|source| source.rect)
+                .collect::<Vec<_>>(),
+            [
+                URect::new(0, 2, 2, 3),
+                URect::new(0, 2, 2, 4),
+                URect::new(0, 0, 2, 2),
+                URect::new(0, 0, 2, 1),
+            ]
+        );
+        for (rect, y) in packed_atlas.layout.textures.iter().zip([2, 2, 0, 0]) {
+            let pixel = packed_atlas
+                .texture
+                .pixel_bytes(rect.min.extend(0))
+                .unwrap();
+            assert_eq!(pixel, [0, y, 0, 255]);
+        }
+    }
+
+    #[test]
+    fn rotate() {
+        let sheet = positional_image(UVec2::new(3, 2));
+
+        for (rotate, positions) in [
+            ("None", [(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]),
+            ("Cw90", [(0, 1), (0, 0), (1, 1), (1, 0), (2, 1), (2, 0)]),
+            ("Cw180", [(2, 1), (1, 1), (0, 1), (2, 0), (1, 0), (0, 0)]),
+            ("Cw270", [(2, 0), (2, 1), (1, 0), (1, 1), (0, 0), (0, 1)]),
+        ] {
+            let titan = ron::de::from_str::<Titan>(&format!(
+                "(textures: [(path: \"sheet.png\", rotate: {rotate}, sprite_sheet: Grid(tile_size: (1, 1)))])"
+            ))
+            .unwrap();
+
+            let packed_atlas = pack_atlas(
+                titan,
+                HashMap::from([("sheet.png".to_string(), sheet.clone())]),
+            )
+            .unwrap();
+
+            assert_eq!(packed_atlas.layout.len(), positions.len());
+            for ((rect, source), (x, y)) in packed_atlas
+                .layout
+                .textures
+                .iter()
+                .zip(&packed_atlas.source_rects)
+                .zip(positions)
+            {
+                let pixel = packed_atlas
+                    .texture
+                    .pixel_bytes(rect.min.extend(0))
+                    .unwrap();
+                assert_eq!(pixel, [x, y, 0, 255], "{rotate}");
+                assert_eq!(
+                    source.rect,
+                    URect::new(x.into(), y.into(), x as u32 + 1, y as u32 + 1)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn overlapping_rects() {
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"a.png\", sprite_sheet: Heterogeneous([((0, 0), (2, 2)), ((2, 0), (2, 2)), ((1, 1), (2, 2))])), (path: \"a.png\", sprite_sheet: Heterogeneous((rects: [((0, 0), (1, 1))], offset: (3, 1)))), (path: \"b.png\", sprite_sheet: Heterogeneous([((0, 0), (2, 2))])), (path: \"a.png\", source_rect: Some((min: (0, 0), max: (4, 4))), sprite_sheet: Heterogeneous([((0, 0), (2, 2))]))])",
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::overlapping_rects(&titan.textures),
+            [((0, 0), (0, 2)), ((0, 1), (0, 2)), ((0, 1), (1, 0))]
+        );
+        /* Only a warning */
+        assert!(validate(&titan).is_ok());
+    }
+
+    #[test]
+    fn base_path() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (base_path: \"v2\"), textures: [(path: \"a.png\"), (path: \"sprites/b.png\"), (path: \"a.png\")])",
+        )
+        .unwrap();
+        let mut paths = Vec::new();
+
+        build_atlas(titan, |path| {
+            paths.push(path.to_string());
+            Image::default()
+        })
+        .unwrap();
+
+        assert_eq!(
+            paths,
+            ["a.png", "sprites/b.png"]
+                .map(|path| Path::new("v2").join(path).to_string_lossy().into_owned())
+        );
+    }
+
+    #[test]
+    fn uvs() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (initial_size: (8, 4), max_size: (8, 4), padding: (0, 0)), textures: [(path: \"a.png\"), (path: \"b.png\")])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(4, 4))),
+            ("b.png".to_string(), image(UVec2::new(2, 2))),
+        ]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(packed_atlas.texture.size(), UVec2::new(8, 4));
+        assert_eq!(packed_atlas.layout.textures[0], URect::new(0, 0, 4, 4));
+        assert_eq!(packed_atlas.layout.textures[1], URect::new(4, 0, 6, 2));
+        assert_eq!(
+            *packed_atlas.uvs(),
+            [
+                Rect::new(0.0, 0.0, 0.5, 1.0),
+                Rect::new(0.5, 0.0, 0.75, 0.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_tags() {
+        let titan = || {
+            ron::de::from_str::<Titan>(
+                "(textures: [(path: \"a.png\", name: Some(\"a\")), (path: \"mobile.png\", name: Some(\"b\"), tags: [\"mobile\"]), (path: \"desktop.png\", name: Some(\"b\"), tags: [\"desktop\", \"console\"]), (path: \"c.png\", name: Some(\"c\"))])",
+            )
+            .unwrap()
+        };
+        let paths = |active_tags: &[&str]| {
+            let mut titan = titan();
+            super::filter_tags(
+                &mut titan,
+                &active_tags
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+            );
+            titan
+                .textures
+                .into_iter()
+                .map(|titan_entry| titan_entry.path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(paths(&[]), ["a.png", "c.png"]);
+        assert_eq!(paths(&["mobile"]), ["a.png", "mobile.png", "c.png"]);
+        assert_eq!(paths(&["console", "tv"]), ["a.png", "desktop.png", "c.png"]);
+    }
+
+    #[test]
+    fn unknown_fields() {
+        for (manifest, field) in [
+            ("(configuration: (max_sizes: (8, 8)), textures: [])", "max_sizes"),
+            ("(textures: [(path: \"a.png\", nmae: Some(\"a\"))])", "nmae"),
+            ("(textures: [(path: \"a.png\", sprite_sheet: Homogeneous(tile_size: (2, 2), columns: 2, rows: 1, row: 1))])", "row"),
+            ("(textures: Grid(path: \"a.png\", tilesize: (2, 2)))", "tilesize"),
+        ] {
+            let error = ron::de::from_str::<Titan>(manifest).unwrap_err();
+            assert!(error.to_string().contains(field), "{field}: {error}");
+        }
+
+        /* Every bundled manifest only uses known fields */
+        let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        for entry in std::fs::read_dir(assets).unwrap() {
+            let path = entry.unwrap().path();
+            if path.to_string_lossy().ends_with(".titan.ron") {
+                let bytes = std::fs::read(&path).unwrap();
+                ron::de::from_bytes::<Titan>(&bytes)
+                    .unwrap_or_else(|error| panic!("{}: {error}", path.display()));
+            }
+        }
+    }
+
+    #[test]
+    fn glob_matches() {
+        assert!(super::glob_matches("*.png", "goblin.png"));
+        assert!(super::glob_matches("*.png", ".png"));
+        assert!(super::glob_matches("goblin_?.png", "goblin_1.png"));
+        assert!(super::glob_matches("*_*_idle*", "orc_big_idle_0.png"));
+        assert!(!super::glob_matches("*.png", "goblin.jpg"));
+        assert!(!super::glob_matches("goblin_?.png", "goblin_10.png"));
+        assert!(!super::glob_matches("goblin.png", "goblin.png.bak"));
+    }
+
+    #[test]
+    fn glob() {
+        let (mut app, assets) = asset_app("glob", false);
+        std::fs::create_dir_all(assets.join("v1/enemies/nested.png")).unwrap();
+        for (name, width) in [("orc", 3), ("goblin", 1), ("bat", 2)] {
+            image::RgbaImage::new(width, 1)
+                .save(assets.join(format!("v1/enemies/{name}.png")))
+                .unwrap();
+        }
+        std::fs::write(assets.join("v1/enemies/notes.txt"), "").unwrap();
+        std::fs::write(
+            assets.join("enemies.titan.ron"),
+            "(configuration: (base_path: \"v1\"), textures: [(glob: Some(\"enemies/*.png\"))])",
+        )
+        .unwrap();
+        std::fs::write(
+            assets.join("empty.titan.ron"),
+            "(textures: [(glob: Some(\"v1/enemies/*.jpg\"))])",
+        )
+        .unwrap();
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle = asset_server.load::<TextureAtlas>("enemies.titan.ron");
+        let source_rects_handle =
+            asset_server.load::<TextureAtlasSourceRects>("enemies.titan.ron#source_rects");
+        let empty_handle = asset_server.load::<TextureAtlas>("empty.titan.ron");
+        let start = Instant::now();
+        while !(asset_server.is_loaded_with_dependencies(&handle)
+            && asset_server.load_state(&empty_handle).is_failed())
+        {
+            assert!(start.elapsed() < Duration::from_secs(30), "Timed out");
+            assert!(!asset_server.load_state(&handle).is_failed());
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let texture_atlas = app
+            .world()
+            .resource::<Assets<TextureAtlas>>()
+            .get(&handle)
+            .unwrap();
+        let names = app
+            .world()
+            .resource::<Assets<TextureAtlasNames>>()
+            .get(&texture_atlas.names)
+            .unwrap();
+        let source_rects = app
+            .world()
+            .resource::<Assets<TextureAtlasSourceRects>>()
+            .get(&source_rects_handle)
+            .unwrap();
+
+        /* Sorted by path, named after the file stem */
+        assert_eq!(texture_atlas.frame_count, 3);
+        for (index, (name, width)) in [("bat", 2), ("goblin", 1), ("orc", 3)]
+            .into_iter()
+            .enumerate()
+        {
+            assert_eq!(names[name], index);
+            assert_eq!(
+                source_rects[index],
+                SpriteSource {
+                    path: format!("v1/enemies/{name}.png"),
+                    rect: URect::new(0, 0, width, 1),
+                    transformed: false,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn source_too_large() {
+        let titan = |configuration: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (initial_size: (8, 8), max_size: (8, 8), {configuration}), textures: [(path: \"small.png\"), (path: \"big.png\")])"
+            ))
+            .unwrap()
+        };
+        let source_images = || {
+            HashMap::from([
+                ("small.png".to_string(), image(UVec2::new(2, 2))),
+                ("big.png".to_string(), image(UVec2::new(4, 8))),
+            ])
+        };
+
+        assert!(pack_atlas(titan("padding: (0, 0)"), source_images()).is_ok());
+        assert!(matches!(
+            pack_atlas(titan("padding: (0, 1)"), source_images()),
+            Err(SpriteSheetLoaderError::SourceTooLarge { path, size, max_size })
+                if path == "big.png" && size == UVec2::new(4, 8) && max_size == UVec2::new(8, 8)
+        ));
+        assert!(pack_atlas(
+            titan("padding: (0, 1), allow_rotation: true"),
+            source_images()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn texture_format_aliases() {
+        for (name, format) in [
+            ("rgba8-srgb", TextureFormat::Rgba8UnormSrgb),
+            ("rgba8", TextureFormat::Rgba8Unorm),
+            ("r8", TextureFormat::R8Unorm),
+            ("rgba8unorm-srgb", TextureFormat::Rgba8UnormSrgb),
+            ("bgra8unorm", TextureFormat::Bgra8Unorm),
+        ] {
+            let titan = ron::de::from_str::<Titan>(&format!(
+                "(configuration: (format: \"{name}\"), textures: [(path: \"a.png\", format: Some(\"{name}\"))])"
+            ))
+            .unwrap();
+            assert_eq!(titan.configuration.format, format);
+            assert_eq!(titan.textures[0].format, Some(format));
+        }
+        let titan = serde_json::from_str::<Titan>(
+            r#"{"textures": [{"data": {"Inline": ["AAAAAA==", "r8", [2, 2]]}}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            titan.textures[0].data,
+            Some(TitanData::Inline(_, TextureFormat::R8Unorm, _))
+        ));
+
+        let error =
+            ron::de::from_str::<Titan>("(configuration: (format: \"rgba9\"), textures: [])")
+                .unwrap_err();
+        assert!(error.to_string().contains("unknown texture format rgba9"));
+    }
+
+    #[test]
+    fn entry_auto_format_conversion() {
+        let titan = |auto_format_conversion: bool, entry: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (format: \"rgba8-srgb\", auto_format_conversion: {auto_format_conversion}), textures: [(path: \"mask.png\", auto_format_conversion: Some(false)), (path: \"b.png\", {entry})])"
+            ))
+            .unwrap()
+        };
+        let source_images = || {
+            let mut bgra = image(UVec2::new(2, 2));
+            bgra.texture_descriptor.format = TextureFormat::Bgra8UnormSrgb;
+            HashMap::from([
+                ("mask.png".to_string(), image(UVec2::new(2, 2))),
+                ("b.png".to_string(), bgra),
+            ])
+        };
+
+        let packed_atlas = pack_atlas(titan(true, ""), source_images()).unwrap();
+        assert_eq!(
+            packed_atlas.texture.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+        assert!(matches!(
+            pack_atlas(titan(true, "auto_format_conversion: Some(false)"), source_images()),
+            Err(SpriteSheetLoaderError::FormatMismatch { path, .. }) if path == "b.png"
+        ));
+        /* Converted for the entry, although the combined texture atlas does not convert */
+        let packed_atlas = pack_atlas(
+            titan(false, "auto_format_conversion: Some(true)"),
+            source_images(),
+        )
+        .unwrap();
+        assert_eq!(
+            packed_atlas.texture.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+    }
+
+    #[test]
+    fn load_titan_from_str() {
+        let ron = "(configuration: (padding: (1, 1)), textures: [(path: \"a.png\", name: Some(\"a\")), (path: \"b.png\")])";
+        let images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(2, 2))),
+            ("b.png".to_string(), image(UVec2::new(3, 1))),
+        ]);
+
+        let (layout, _) = super::load_titan_from_str(ron, &images).unwrap();
+        assert_eq!(layout.len(), 2);
+        let mut sizes = layout.textures.iter().map(URect::size).collect::<Vec<_>>();
+        sizes.sort_by_key(|size| (size.x, size.y));
+        assert_eq!(sizes, [UVec2::new(2, 2), UVec2::new(3, 1)]);
+
+        let missing = HashMap::from([("a.png".to_string(), image(UVec2::new(2, 2)))]);
+        assert!(matches!(
+            super::load_titan_from_str(ron, &missing),
+            Err(SpriteSheetLoaderError::ReadAssetBytesError(_))
+        ));
+        assert!(super::load_titan_from_str("(textures: [(unknown: 1)])", &images).is_err());
+
+        let ignore_missing = "(configuration: (ignore_missing: true, base_path: \"sprites\"), textures: [(path: \"a.png\"), (path: \"b.png\")])";
+        let images = HashMap::from([("sprites/a.png".to_string(), image(UVec2::new(2, 2)))]);
+        let (layout, _) = super::load_titan_from_str(ignore_missing, &images).unwrap();
+        assert_eq!(layout.len(), 1);
+        assert_eq!(layout.textures[0].size(), UVec2::new(2, 2));
+    }
+
+    #[test]
+    fn power_of_two() {
+        let titan = |power_of_two: bool| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (initial_size: (5, 3), padding: (0, 0), power_of_two: {power_of_two}), textures: [(path: \"a.png\"), (path: \"b.png\")])"
+            ))
+            .unwrap()
+        };
+        let source_images = || {
+            HashMap::from([
+                ("a.png".to_string(), image(UVec2::new(5, 3))),
+                ("b.png".to_string(), image(UVec2::new(3, 3))),
+            ])
+        };
+
+        let unpadded = pack_atlas(titan(false), source_images()).unwrap();
+        let packed_atlas = pack_atlas(titan(true), source_images()).unwrap();
+
+        let size = unpadded.texture.size();
+        assert_ne!(size, size.map(u32::next_power_of_two));
+        assert_eq!(
+            packed_atlas.texture.size(),
+            size.map(u32::next_power_of_two)
+        );
+        assert_eq!(packed_atlas.layout.size, packed_atlas.texture.size());
+        assert_eq!(packed_atlas.layout.textures, unpadded.layout.textures);
+        /* The extra space is transparent */
+        let pixel_size = 4;
+        let row_len = packed_atlas.texture.width() as usize * pixel_size;
+        for (y, row) in packed_atlas.texture.data.chunks_exact(row_len).enumerate() {
+            let extra = if y < size.y as usize {
+                &row[size.x as usize * pixel_size..]
+            } else {
+                row
+            };
+            assert!(extra.iter().all(|&value| value == 0));
+        }
+    }
+
+    #[test]
+    fn load_progress() {
+        let (mut app, assets) = asset_app("progress", false);
+        for name in ["a", "b"] {
+            image::RgbaImage::new(2, 2)
+                .save(assets.join(format!("{name}.png")))
+                .unwrap();
+        }
+        std::fs::write(
+            assets.join("sheet.titan.ron"),
+            "(textures: [(path: \"a.png\"), (path: \"b.png\"), (path: \"a.png\")])",
+        )
+        .unwrap();
+
+        let progress = app.world().resource::<TitanLoadProgress>().clone();
+        assert_eq!(progress.images(), 0);
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let handle = asset_server.load::<TextureAtlas>("sheet.titan.ron");
+        let start = Instant::now();
+        while !asset_server.is_loaded_with_dependencies(&handle) {
+            assert!(start.elapsed() < Duration::from_secs(30), "Timed out");
+            assert!(!asset_server.load_state(&handle).is_failed());
+            assert!(progress.decoded() <= progress.images());
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        /* Every distinct image is decoded once */
+        assert_eq!(progress.loading(), 0);
+        assert_eq!(progress.images(), 2);
+        assert_eq!(progress.decoded(), 2);
+    }
+
+    #[test]
+    fn layout_snapshot() {
+        let ron = |padding: u32| {
+            format!("(configuration: (padding: ({padding}, {padding})), textures: [(path: \"a.png\"), (path: \"b.png\")])")
+        };
+        let images = HashMap::from([
+            ("a.png".to_string(), image(UVec2::new(4, 4))),
+            ("b.png".to_string(), image(UVec2::new(2, 2))),
+        ]);
+
+        let (layout, _) = super::load_titan_from_str(&ron(0), &images).unwrap();
+        let snapshot = LayoutSnapshot::from(&layout);
+        assert_eq!(snapshot.rects, layout.textures);
+        assert_eq!(snapshot.page_indices, [0, 0]);
+        let serialized = ron::ser::to_string(&snapshot).unwrap();
+        assert_eq!(
+            ron::de::from_str::<LayoutSnapshot>(&serialized).unwrap(),
+            snapshot
+        );
+        assert!(snapshot.changed_indices(&snapshot).is_empty());
+
+        /* More padding moves the second sprite */
+        let (padded_layout, _) = super::load_titan_from_str(&ron(2), &images).unwrap();
+        let padded_snapshot = LayoutSnapshot::from(&padded_layout);
+        assert!(!padded_snapshot.changed_indices(&snapshot).is_empty());
+        let mut shorter = snapshot.clone();
+        shorter.rects.pop();
+        shorter.page_indices.pop();
+        assert_eq!(shorter.changed_indices(&snapshot), [1]);
+
+        let mut layouts = Assets::<TextureAtlasLayout>::default();
+        let texture_atlas = TextureAtlas {
+            texture: Handle::default(),
+            layout: layouts.add(layout),
+            frame_count: 2,
+            atlas_size: UVec2::ZERO,
+            names: Handle::default(),
+            animations: Handle::default(),
+            pages: Vec::new(),
+            page_indices: vec![0, 1],
+            version: None,
+            checksum: 0,
+            default_index: None,
+        };
+        let page_snapshot = texture_atlas.layout_snapshot(&layouts).unwrap();
+        assert_eq!(page_snapshot.rects, snapshot.rects);
+        assert_eq!(page_snapshot.changed_indices(&snapshot), [1]);
+    }
+
+    #[test]
+    fn padding_rect() {
+        /* Tiles of size 2 that are only padded by 1 to the right and by 2 below */
+        let homogeneous = |border_padding: &str| {
+            ron::de::from_str::<TitanSpriteSheet>(&format!(
+                "Homogeneous(tile_size: (2, 2), columns: 2, rows: 2, padding: (5, 5), padding_rect: Some((right: 1, bottom: 2)){border_padding})"
+            ))
+            .unwrap()
+        };
+        let expected = [(0, 0), (3, 0), (0, 4), (3, 4)]
+            .map(|(x, y)| URect::from_corners(UVec2::new(x, y), UVec2::new(x + 2, y + 2)));
+
+        assert_eq!(
+            sprite_rects(&homogeneous(""), &image(UVec2::new(5, 6)), "", 0, false).unwrap(),
+            expected
+        );
+        assert!(matches!(
+            sprite_rects(&homogeneous(""), &image(UVec2::new(5, 5)), "", 0, false),
+            Err(SpriteSheetLoaderError::GridOutOfBounds { required_size, .. }) if required_size == UVec2::new(5, 6)
+        ));
+        assert_eq!(
+            sprite_rects(
+                &homogeneous(", border_padding: Some((1, 1))"),
+                &image(UVec2::new(6, 7)),
+                "",
+                0,
+                false
+            )
+            .unwrap(),
+            expected.map(|rect| URect::from_corners(rect.min + 1, rect.max + 1))
+        );
+        assert!(ron::de::from_str::<TitanSpriteSheet>(
+            "Homogeneous(tile_size: (2, 2), columns: 1, rows: 1, padding_rect: Some((rigth: 1)))"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn empty_grid() {
+        let titan = |columns: u32, rows: u32| {
+            ron::de::from_str::<Titan>(&format!(
+                "(textures: [(path: \"a.png\"), (path: \"b.png\", sprite_sheet: Homogeneous(tile_size: (2, 2), columns: {columns}, rows: {rows}))])"
+            ))
+            .unwrap()
+        };
+
+        assert!(validate(&titan(1, 1)).is_ok());
+        assert!(matches!(
+            validate(&titan(0, 2)),
+            Err(SpriteSheetLoaderError::EmptyGridError(0, 2, 1))
+        ));
+        assert!(matches!(
+            validate(&titan(3, 0)),
+            Err(SpriteSheetLoaderError::EmptyGridError(3, 0, 1))
+        ));
+    }
+
+    #[test]
+    fn groups() {
+        let titan = ron::de::from_str::<Titan>(
+            "(textures: [(path: \"idle.png\", name: Some(\"idle\"), sprite_sheet: Homogeneous(tile_size: (2, 2), columns: 3, rows: 1)), (path: \"icon.png\"), (path: \"run.png\", name: Some(\"run\"), sprite_sheet: Grid(tile_size: (1, 1)), flip: Some((x: true)))])",
+        )
+        .unwrap();
+        let source_images = HashMap::from([
+            ("idle.png".to_string(), image(UVec2::new(6, 2))),
+            ("icon.png".to_string(), image(UVec2::new(4, 4))),
+            ("run.png".to_string(), image(UVec2::new(2, 1))),
+        ]);
+
+        let packed_atlas = pack_atlas(titan, source_images).unwrap();
+
+        assert_eq!(packed_atlas.layout.len(), 8);
+        let groups = TextureAtlasGroups(packed_atlas.groups);
+        assert_eq!(
+            groups
+                .iter()
+                .map(|group| (group.name.as_deref(), group.indices.clone()))
+                .collect::<Vec<_>>(),
+            [(Some("idle"), 0..3), (None, 3..4), (Some("run"), 4..8)]
+        );
+        assert_eq!(groups.indices("run"), Some(4..8));
+        assert_eq!(groups.indices("jump"), None);
+    }
+
+    #[test]
+    fn texture_usages() {
+        let titan = |configuration: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: ({configuration}), textures: [(path: \"a.png\")])"
+            ))
+        };
+        let source_images = || HashMap::from([("a.png".to_string(), image(UVec2::new(2, 2)))]);
+
+        let packed_atlas = pack_atlas(titan("").unwrap(), source_images()).unwrap();
+        assert_eq!(
+            packed_atlas.texture.texture_descriptor.usage,
+            TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST
+        );
+
+        let usages = TextureUsages::TEXTURE_BINDING
+            | TextureUsages::COPY_DST
+            | TextureUsages::STORAGE_BINDING;
+        let storage = titan(
+            "format: \"rgba8\", texture_usages: \"TEXTURE_BINDING | COPY_DST | STORAGE_BINDING\"",
+        )
+        .unwrap();
+        let serialized = ron::ser::to_string(&storage.configuration).unwrap();
+        assert!(serialized.contains("\"COPY_DST | TEXTURE_BINDING | STORAGE_BINDING\""));
+        assert_eq!(
+            ron::de::from_str::<TitanConfiguration>(&serialized)
+                .unwrap()
+                .texture_usages,
+            usages
+        );
+        let packed_atlas = pack_atlas(storage, source_images()).unwrap();
+        assert_eq!(packed_atlas.texture.texture_descriptor.usage, usages);
+
+        assert!(titan("texture_usages: \"TEXTURE_BINDING | STORAGE\"").is_err());
+    }
+
+    #[test]
+    fn alpha_threshold() {
+        let titan = |alpha_threshold: &str| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (always_pack: false, alpha_threshold: {alpha_threshold}), textures: [(path: \"edge.png\")])"
+            ))
+            .unwrap()
+        };
+        /* An anti-aliased edge that fades out from left to right */
+        let alphas = [255, 200, 128, 127, 40, 0];
+        let mut edge = image(UVec2::new(alphas.len() as u32, 1));
+        for (pixel, alpha) in edge.data.chunks_exact_mut(4).zip(alphas) {
+            pixel[3] = alpha;
+        }
+        let alpha_channel = |image: &Image| {
+            image
+                .data
+                .chunks_exact(4)
+                .map(|pixel| pixel[3])
+                .collect::<Vec<_>>()
+        };
+
+        let packed_atlas = pack_atlas(
+            titan("Some(128)"),
+            HashMap::from([("edge.png".to_string(), edge.clone())]),
+        )
+        .unwrap();
+        assert_eq!(
+            alpha_channel(&packed_atlas.texture),
+            [255, 255, 255, 0, 0, 0]
+        );
+        /* The color channels are kept */
+        assert!(packed_atlas
+            .texture
+            .data
+            .chunks_exact(4)
+            .all(|pixel| pixel[..3] == [255, 0, 0]));
+
+        let packed_atlas = pack_atlas(
+            titan("None"),
+            HashMap::from([("edge.png".to_string(), edge)]),
+        )
+        .unwrap();
+        assert_eq!(alpha_channel(&packed_atlas.texture), alphas);
+
+        let mut gray = Image::new_fill(
+            Extent3d::default(),
+            TextureDimension::D2,
+            &[40],
+            TextureFormat::R8Unorm,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        assert!(!snap_alpha(&mut gray, 128));
+        assert_eq!(gray.data, [40]);
+    }
+
+    #[test]
+    fn premultiply() {
+        let titan = ron::de::from_str::<Titan>(
+            "(configuration: (always_pack: false, premultiply_alpha: true), textures: [(path: \"a.png\")])",
+        )
+        .unwrap();
+        let mut source = image(UVec2::new(2, 1));
+        source.data[4..].copy_from_slice(&[200, 100, 50, 128]);
+
+        let packed_atlas =
+            pack_atlas(titan, HashMap::from([("a.png".to_string(), source)])).unwrap();
+        assert_eq!(
+            packed_atlas.texture.data,
+            [255, 0, 0, 255, 100, 50, 25, 128]
+        );
+
+        /* Signed formats are left untouched */
+        let mut snorm = Image::new_fill(
+            Extent3d::default(),
+            TextureDimension::D2,
+            &[100, 200, 50, 128],
+            TextureFormat::Rgba8Snorm,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        assert!(!premultiply_alpha(&mut snorm));
+        assert_eq!(snorm.data, [100, 200, 50, 128]);
+    }
+
+    #[test]
+    fn color_key() {
+        let titan = |color_key_tolerance: u8| {
+            ron::de::from_str::<Titan>(&format!(
+                "(configuration: (always_pack: false, color_key: Some((255, 0, 255)), color_key_tolerance: {color_key_tolerance}), textures: [(path: \"a.png\")])"
+            ))
+            .unwrap()
+        };
+        let mut source = image(UVec2::new(3, 1));
+        source.data[4..].copy_from_slice(&[255, 0, 255, 255, 250, 4, 255, 255]);
+
+        let packed_atlas = pack_atlas(
+            titan(0),
+            HashMap::from([("a.png".to_string(), source.clone())]),
+        )
+        .unwrap();
+        assert_eq!(
+            packed_atlas.texture.data,
+            [255, 0, 0, 255, 255, 0, 255, 0, 250, 4, 255, 255]
+        );
+
+        /* Colors within the tolerance are keyed as well */
+        let packed_atlas =
+            pack_atlas(titan(5), HashMap::from([("a.png".to_string(), source)])).unwrap();
+        assert_eq!(
+            packed_atlas.texture.data,
+            [255, 0, 0, 255, 255, 0, 255, 0, 250, 4, 255, 0]
+        );
+    }
 }
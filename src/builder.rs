@@ -0,0 +1,117 @@
+//! This module handles packing images that are created at runtime.
+//!
+//! [`TitanBuilder`] runs the same pipeline as the [`SpriteSheetLoader`](crate::asset_loader::SpriteSheetLoader),
+//! but takes decoded images instead of a titan ron file and the image assets it references.
+
+use bevy::{asset::LoadContext, image::Image, sprite::TextureAtlasLayout, utils::HashMap};
+
+use crate::{
+    asset_loader::{
+        add_labeled_assets, pack_titan, validate, AtlasLabels, PackedAtlas, SpriteSheetLoaderError,
+        TextureAtlas,
+    },
+    manifest::{Titan, TitanAnimation, TitanConfiguration, TitanEntry, TitanSpriteSheet},
+};
+
+/// Builds a combined texture atlas from images, without a titan ron file.
+///
+/// ```
+/// # use bevy::{image::Image, math::UVec2};
+/// # use bevy_titan::prelude::*;
+/// let (layout, texture) = TitanBuilder::new()
+///     .add_image(Image::default(), TitanSpriteSheet::None)
+///     .add_image(
+///         Image::default(),
+///         TitanSpriteSheet::Grid {
+///             tile_size: UVec2::ONE,
+///             padding: UVec2::ZERO,
+///             border_padding: None,
+///             offset: UVec2::ZERO,
+///         },
+///     )
+///     .build_standalone()
+///     .unwrap();
+/// assert_eq!(layout.len(), 2);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TitanBuilder {
+    titan: Titan,
+    images: HashMap<String, Image>,
+}
+
+impl TitanBuilder {
+    /// Creates a builder without any images and with the default [`TitanConfiguration`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the configuration of the combined texture atlas.
+    pub fn configuration(mut self, configuration: TitanConfiguration) -> Self {
+        self.titan.configuration = configuration;
+        self
+    }
+
+    /// Adds an image that is split into sprites according to `sprite_sheet`.
+    pub fn add_image(self, image: Image, sprite_sheet: TitanSpriteSheet) -> Self {
+        self.add_entry(
+            image,
+            TitanEntry {
+                sprite_sheet,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Adds an image that is interpreted like a [`TitanEntry`] of a titan ron file.
+    ///
+    /// `path` and `data` of the entry are ignored, the sprites are extracted from `image` instead.
+    pub fn add_entry(mut self, image: Image, mut titan_entry: TitanEntry) -> Self {
+        titan_entry.path = format!("<image {}>", self.titan.textures.len());
+        titan_entry.data = None;
+        self.images.insert(titan_entry.path.clone(), image);
+        self.titan.textures.push(titan_entry);
+        self
+    }
+
+    /// Adds a named animation.
+    pub fn add_animation(mut self, animation: TitanAnimation) -> Self {
+        self.titan.animations.push(animation);
+        self
+    }
+
+    /// Packs all images and adds the texture, the layout and all metadata as labeled assets,
+    /// just like the [`SpriteSheetLoader`](crate::asset_loader::SpriteSheetLoader).
+    ///
+    /// This is meant to be used from within another [`AssetLoader`](bevy::asset::AssetLoader).
+    pub fn build(
+        self,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<TextureAtlas, SpriteSheetLoaderError> {
+        let packed_atlas = self.pack()?;
+
+        Ok(add_labeled_assets(
+            load_context,
+            packed_atlas,
+            AtlasLabels::default(),
+        ))
+    }
+
+    /// Packs all images and returns the layout and the combined texture atlas. All other metadata is dropped.
+    ///
+    /// `allow_multiple_pages` is ignored, all sprites have to fit into a single page.
+    pub fn build_standalone(
+        mut self,
+    ) -> Result<(TextureAtlasLayout, Image), SpriteSheetLoaderError> {
+        self.titan.configuration.allow_multiple_pages = false;
+        let packed_atlas = self.pack()?;
+
+        Ok((packed_atlas.layout, packed_atlas.texture))
+    }
+
+    fn pack(mut self) -> Result<PackedAtlas, SpriteSheetLoaderError> {
+        validate(&self.titan)?;
+        pack_titan(self.titan, |path| {
+            Ok(self.images.remove(path).expect("Every entry has an image"))
+        })
+    }
+}
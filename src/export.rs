@@ -0,0 +1,320 @@
+//! This module handles packing a titan ron file offline.
+//!
+//! [`export_atlas`] runs the same pipeline as the [`SpriteSheetLoader`](crate::asset_loader::SpriteSheetLoader),
+//! but reads from and writes to the file system directly. This allows to bake a combined texture atlas once,
+//! e.g. in a build script, instead of packing it at runtime.
+
+use std::{fs, path::Path};
+
+use bevy::{
+    image::{
+        CompressedImageFormats, Image, ImageSampler, ImageType, IntoDynamicImageError,
+        TextureError, TextureFormatPixelInfo,
+    },
+    math::UVec2,
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{TextureDescriptor, TextureFormat},
+    },
+    utils::HashMap,
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    asset_loader::{
+        apply_base_path, filter_tags, image_format, pack_titan, skip_missing, source_paths,
+        validate, PackedAtlas, SpriteSheetLoaderError,
+    },
+    image_utils::from_dynamic,
+    manifest::{configuration_fields, extend, Checksum, Titan, TitanSyntax},
+};
+
+/// Possible errors that can be produced by [`export_atlas`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ExportError {
+    /// An [IOError](std::io::Error) for the file at the given path.
+    #[error("Could not access file {0}: {1}")]
+    IoError(String, std::io::Error),
+    /// A [RonSpannedError](ron::error::SpannedError).
+    #[error("Could not parse RON: {0}")]
+    RonSpannedError(#[from] ron::error::SpannedError),
+    /// A [RonError](ron::Error).
+    #[error("Could not write RON: {0}")]
+    RonError(#[from] ron::Error),
+    /// An [`ImageError`](image::ImageError) for the image at the given path.
+    #[error("Could not decode or encode image {0}: {1}")]
+    ImageError(String, image::ImageError),
+    /// A [`TextureError`] for the image at the given path.
+    #[error("Could not decode image {0}: {1}")]
+    TextureError(String, TextureError),
+    /// An [`IntoDynamicImageError`].
+    #[error("Could not convert the combined texture atlas: {0}")]
+    IntoDynamicImageError(#[from] IntoDynamicImageError),
+    /// A [`SpriteSheetLoaderError`].
+    #[error("SpriteSheetLoaderError: {0}")]
+    SpriteSheetLoaderError(#[from] SpriteSheetLoaderError),
+    /// An UnsupportedEntryError with the index of an entry that can only be resolved by the asset server.
+    #[error(
+        "Entry {0} has a glob or from_atlas, which is only supported by the SpriteSheetLoader"
+    )]
+    UnsupportedEntryError(usize),
+}
+
+/// Layout of an exported combined texture atlas.
+///
+/// Every sprite is written as `(position, size)`, just like a rect of a heterogeneous sprite sheet.
+/// The page of every sprite is only written if there are multiple pages.
+#[derive(Debug, Serialize)]
+struct LayoutFile {
+    size: UVec2,
+    textures: Vec<(UVec2, UVec2)>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pages: Vec<usize>,
+}
+
+/// Packs the titan ron file at `manifest` and writes the combined texture atlas as png to `out_image`
+/// and its layout as ron to `out_layout`.
+///
+/// With `allow_multiple_pages`, every further page is written next to `out_image` with its number appended
+/// to the file stem, e.g. `atlas1.png`.
+///
+/// With `output` Array, the first mip level of every layer is written, stacked from top to bottom.
+/// [`Image::reinterpret_stacked_2d_as_array`] turns the written png back into an array texture.
+///
+/// All image paths of the titan ron file are resolved relative to `assets`. Every image format that is enabled
+/// in bevy is supported, other formats fail with [`SpriteSheetLoaderError::UnsupportedImageFormat`].
+/// Tagged entries are skipped and `glob` or `from_atlas` entries are rejected.
+pub fn export_atlas(
+    assets: &Path,
+    manifest: &Path,
+    out_image: &Path,
+    out_layout: &Path,
+) -> Result<(), ExportError> {
+    let titan = read_titan(assets, manifest, &mut Checksum::default())?;
+
+    export_titan(titan, assets, out_image, out_layout)
+}
+
+/// Hashes the content of the titan ron file at `manifest`, of every titan ron file it extends and of every image
+/// it references, without packing anything.
+///
+/// A build script can store the checksum next to the exported atlas and skip [`export_atlas`] as long as it does
+/// not change. Like [`export_atlas`], manifests with `glob` or `from_atlas` entries are rejected, for all others
+/// it matches [`TextureAtlas::checksum`](crate::asset_loader::TextureAtlas::checksum) of an atlas loaded with
+/// the default settings.
+pub fn checksum(assets: &Path, manifest: &Path) -> Result<u64, ExportError> {
+    let mut checksum = Checksum::default();
+    let titan = read_titan(assets, manifest, &mut checksum)?;
+    check_entries(&titan)?;
+    let ignore_missing = titan.configuration.ignore_missing;
+    for path in source_paths(&titan.textures) {
+        let Some(bytes) = read_source(assets, path, ignore_missing)? else {
+            continue;
+        };
+        checksum.update(path.as_bytes());
+        checksum.update(&bytes);
+    }
+
+    Ok(checksum.finish())
+}
+
+/// Reads the titan ron file at `manifest` and resolves `extends`, hashing every file read.
+///
+/// Tagged entries are removed, just like the loader does without any active tags.
+fn read_titan(
+    assets: &Path,
+    manifest: &Path,
+    checksum: &mut Checksum,
+) -> Result<Titan, ExportError> {
+    let manifest_path = assets.join(manifest);
+    let bytes = read(&manifest_path)?;
+    checksum.update(&bytes);
+    let syntax = TitanSyntax::from_path(manifest);
+    let mut titan = syntax.deserialize::<Titan>(&bytes)?;
+    if titan.extends.is_some() {
+        let mut fields = configuration_fields(&bytes, syntax)?;
+        let mut visited = vec![manifest.to_string_lossy().into_owned()];
+        let mut extends = titan.extends.clone();
+        while let Some(path) = extends {
+            if visited.contains(&path) {
+                return Err(SpriteSheetLoaderError::CyclicExtendsError(path).into());
+            }
+            let base_bytes = read(&assets.join(&path))?;
+            checksum.update(&base_bytes);
+            extends = extend(&mut titan.configuration, &mut fields, &path, &base_bytes)?;
+            visited.push(path);
+        }
+    }
+    filter_tags(&mut titan, &[]);
+    apply_base_path(&mut titan);
+
+    Ok(titan)
+}
+
+/// Rejects `glob` and `from_atlas` entries, which are resolved through the asset server.
+fn check_entries(titan: &Titan) -> Result<(), ExportError> {
+    match titan
+        .textures
+        .iter()
+        .position(|titan_entry| titan_entry.glob.is_some() || titan_entry.from_atlas.is_some())
+    {
+        Some(index) => Err(ExportError::UnsupportedEntryError(index)),
+        None => Ok(()),
+    }
+}
+
+/// Like [`export_atlas`], but packs an already parsed or programmatically constructed [`Titan`].
+///
+/// `extends` is not resolved, the configuration of `titan` is used as is.
+pub fn export_titan(
+    mut titan: Titan,
+    assets: &Path,
+    out_image: &Path,
+    out_layout: &Path,
+) -> Result<(), ExportError> {
+    apply_base_path(&mut titan);
+    check_entries(&titan)?;
+    validate(&titan)?;
+
+    let mut source_images = HashMap::new();
+    let single_channel = titan.configuration.format == TextureFormat::R8Unorm;
+    let ignore_missing = titan.configuration.ignore_missing;
+    let mut missing = Vec::new();
+    for path in source_paths(&titan.textures) {
+        let Some(bytes) = read_source(assets, path, ignore_missing)? else {
+            missing.push(path.to_string());
+            continue;
+        };
+        source_images.insert(path.to_string(), decode(path, &bytes, single_channel)?);
+    }
+
+    skip_missing(&mut titan, &missing)?;
+
+    let PackedAtlas {
+        layout,
+        texture,
+        additional_pages,
+        page_indices,
+        ..
+    } = pack_titan(titan, |path| {
+        Ok(source_images
+            .remove(path)
+            .expect("Every path of the manifest has a decoded image"))
+    })?;
+
+    write_png(stack_layers(texture), out_image)?;
+    let multiple_pages = !additional_pages.is_empty();
+    let stem = out_image
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .into_owned();
+    for (page_index, page) in additional_pages.into_iter().enumerate() {
+        let mut page_path = out_image.with_file_name(format!("{stem}{}", page_index + 1));
+        if let Some(extension) = out_image.extension() {
+            page_path.set_extension(extension);
+        }
+        write_png(page, &page_path)?;
+    }
+
+    let layout_file = LayoutFile {
+        size: layout.size,
+        textures: layout
+            .textures
+            .iter()
+            .map(|rect| (rect.min, rect.size()))
+            .collect(),
+        pages: if multiple_pages {
+            page_indices
+        } else {
+            Vec::new()
+        },
+    };
+    let layout_ron = ron::ser::to_string_pretty(&layout_file, Default::default())?;
+    fs::write(out_layout, layout_ron)
+        .map_err(|error| ExportError::IoError(out_layout.display().to_string(), error))?;
+
+    Ok(())
+}
+
+/// Decodes the image at `path` like the [`ImageLoader`](bevy::image::ImageLoader) does with its default settings.
+///
+/// With `single_channel` pngs are decoded directly instead, so grayscale ones are not expanded to RGBA.
+fn decode(path: &str, bytes: &[u8], single_channel: bool) -> Result<Image, ExportError> {
+    if single_channel && image::ImageFormat::from_path(path).ok() == Some(image::ImageFormat::Png) {
+        return image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+            .map(|dynamic_image| from_dynamic(dynamic_image, true))
+            .map_err(|error| ExportError::ImageError(path.to_string(), error));
+    }
+
+    Image::from_buffer(
+        #[cfg(all(debug_assertions, feature = "dds"))]
+        path.to_string(),
+        bytes,
+        ImageType::Format(image_format(path)?),
+        CompressedImageFormats::NONE,
+        true,
+        ImageSampler::Default,
+        RenderAssetUsages::default(),
+    )
+    .map_err(|error| ExportError::TextureError(path.to_string(), error))
+}
+
+/// Stacks the first mip level of every layer of an array texture from top to bottom into a single 2D image.
+fn stack_layers(texture: Image) -> Image {
+    let format = texture.texture_descriptor.format;
+    let layers = texture.texture_descriptor.size.depth_or_array_layers;
+    if layers == 1 || format.is_compressed() {
+        return texture;
+    }
+
+    let mut size = texture.texture_descriptor.size;
+    let level_len = (size.width * size.height) as usize * format.pixel_size();
+    let data = texture
+        .data
+        .chunks_exact(texture.data.len() / layers as usize)
+        .flat_map(|layer| &layer[..level_len])
+        .copied()
+        .collect();
+    size.height *= layers;
+    size.depth_or_array_layers = 1;
+
+    Image {
+        data,
+        texture_descriptor: TextureDescriptor {
+            size,
+            mip_level_count: 1,
+            ..texture.texture_descriptor
+        },
+        texture_view_descriptor: None,
+        ..texture
+    }
+}
+
+fn write_png(texture: Image, path: &Path) -> Result<(), ExportError> {
+    texture
+        .try_into_dynamic()?
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|error| ExportError::ImageError(path.display().to_string(), error))
+}
+
+/// Reads the image at `path`. Returns `None` if it does not exist and `ignore_missing` is set.
+fn read_source(
+    assets: &Path,
+    path: &str,
+    ignore_missing: bool,
+) -> Result<Option<Vec<u8>>, ExportError> {
+    let path = assets.join(path);
+    match fs::read(&path) {
+        Err(error) if ignore_missing && error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        result => result
+            .map(Some)
+            .map_err(|error| ExportError::IoError(path.display().to_string(), error)),
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, ExportError> {
+    fs::read(path).map_err(|error| ExportError::IoError(path.display().to_string(), error))
+}
@@ -0,0 +1,591 @@
+//! This module provides helpers to manipulate the pixel data of images before packing.
+
+use bevy::{
+    image::{Image, TextureFormatPixelInfo},
+    math::{Rect, URect, UVec2, Vec2},
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    },
+};
+
+use image::DynamicImage;
+
+use crate::manifest::{TitanChannelMapping, TitanScaleFilter};
+
+/// Converts a decoded image just like the [`ImageLoader`](bevy::image::ImageLoader) does, but keeps an 8 bit grayscale image
+/// without alpha as a single channel [`TextureFormat::R8Unorm`] image if `single_channel` is set.
+pub(crate) fn from_dynamic(dynamic_image: DynamicImage, single_channel: bool) -> Image {
+    match dynamic_image {
+        DynamicImage::ImageLuma8(luma_image) if single_channel => Image::new(
+            Extent3d {
+                width: luma_image.width(),
+                height: luma_image.height(),
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            luma_image.into_raw(),
+            TextureFormat::R8Unorm,
+            RenderAssetUsages::default(),
+        ),
+        dynamic_image => Image::from_dynamic(dynamic_image, true, RenderAssetUsages::default()),
+    }
+}
+
+/// Copies the pixels within `rect` into a new image. Returns `None` if `rect` exceeds the image.
+pub(crate) fn extract_texture_from_rect(image: &Image, rect: URect) -> Option<Image> {
+    if (rect.max.x > image.size().x) || (rect.max.y > image.size().y) {
+        None
+    } else {
+        let format_size = image.texture_descriptor.format.pixel_size();
+        let rect_size = UVec2::new(rect.max.x - rect.min.x, rect.max.y - rect.min.y);
+        let mut data: Vec<u8> = vec![0; (rect_size.x * rect_size.y) as usize * format_size];
+
+        for i in 0..rect_size.y {
+            let data_begin = (rect_size.x * i) as usize * format_size;
+            let data_end = data_begin + rect_size.x as usize * format_size;
+            let texture_atlas_rect_begin = (rect.min.x as usize
+                + (rect.min.y + i) as usize * image.width() as usize)
+                * format_size;
+            let texture_atlas_rect_end =
+                texture_atlas_rect_begin + rect_size.x as usize * format_size;
+
+            data[data_begin..data_end]
+                .copy_from_slice(&image.data[texture_atlas_rect_begin..texture_atlas_rect_end]);
+        }
+
+        let image = Image::new(
+            Extent3d {
+                width: rect_size.x,
+                height: rect_size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            image.texture_descriptor.format,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        Some(image)
+    }
+}
+
+/// Extends the image to `size` with zeroed, i.e. transparent, pixels along its right and bottom edge.
+pub(crate) fn pad_image(image: &Image, size: UVec2) -> Image {
+    let format_size = image.texture_descriptor.format.pixel_size();
+    let row_len = image.width() as usize * format_size;
+    let padded_row_len = size.x as usize * format_size;
+    let mut data = vec![0; padded_row_len * size.y as usize];
+    for (row, padded_row) in image
+        .data
+        .chunks_exact(row_len)
+        .zip(data.chunks_exact_mut(padded_row_len))
+    {
+        padded_row[..row_len].copy_from_slice(row);
+    }
+
+    let mut padded_image = image.clone();
+    padded_image.texture_descriptor.size = Extent3d {
+        width: size.x,
+        height: size.y,
+        depth_or_array_layers: 1,
+    };
+    padded_image.data = data;
+    padded_image
+}
+
+/// Crops the image to the bounding box of all pixels with a non-zero alpha value.
+///
+/// Returns the trimmed image and its offset within the original image.
+/// A fully transparent image is trimmed to a single pixel.
+/// Returns `None` if the format has no unsigned 8 bit alpha channel.
+pub(crate) fn trim(image: &Image) -> Option<(Image, UVec2)> {
+    let alpha = alpha_channel(image.texture_descriptor.format)?;
+    let format_size = image.texture_descriptor.format.pixel_size();
+    let width = image.width();
+
+    let mut min = UVec2::MAX;
+    let mut max = UVec2::ZERO;
+    for (i, pixel) in image.data.chunks_exact(format_size).enumerate() {
+        if pixel[alpha] != 0 {
+            let position = UVec2::new(i as u32 % width, i as u32 / width);
+            min = min.min(position);
+            max = max.max(position + 1);
+        }
+    }
+
+    let rect = if min == UVec2::MAX {
+        URect::new(0, 0, 1, 1)
+    } else {
+        URect::from_corners(min, max)
+    };
+
+    extract_texture_from_rect(image, rect).map(|image| (image, rect.min))
+}
+
+/// Rotates the image by 90 degrees clockwise.
+pub(crate) fn rotate_clockwise(image: &Image) -> Image {
+    rotate(image, |x, y, size| (size.y - 1 - y, x))
+}
+
+/// Rotates the image by 90 degrees counterclockwise.
+pub(crate) fn rotate_counterclockwise(image: &Image) -> Image {
+    rotate(image, |x, y, size| (y, size.x - 1 - x))
+}
+
+/// Mirrors the image horizontally if `x` and vertically if `y` is set.
+pub(crate) fn flip(image: &Image, x: bool, y: bool) -> Image {
+    let format_size = image.texture_descriptor.format.pixel_size();
+    let size = image.size();
+    let mut data = vec![0; image.data.len()];
+    for source_y in 0..size.y {
+        for source_x in 0..size.x {
+            let target_x = if x { size.x - 1 - source_x } else { source_x };
+            let target_y = if y { size.y - 1 - source_y } else { source_y };
+            let source_index = (source_y * size.x + source_x) as usize * format_size;
+            let target_index = (target_y * size.x + target_x) as usize * format_size;
+            data[target_index..target_index + format_size]
+                .copy_from_slice(&image.data[source_index..source_index + format_size]);
+        }
+    }
+
+    Image::new(
+        image.texture_descriptor.size,
+        TextureDimension::D2,
+        data,
+        image.texture_descriptor.format,
+        RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+/// Repeats the last column of `rect` into the `padding.x` columns to its right and the last row,
+/// including those columns, into the `padding.y` rows below. Nothing is written outside the image.
+///
+/// With `wrap` the padding is filled with the columns and rows from the opposite edge instead,
+/// as if the sprite was repeated.
+pub(crate) fn extrude_edges(image: &mut Image, rect: URect, padding: UVec2, wrap: bool) {
+    if rect.is_empty() {
+        return;
+    }
+    let format_size = image.texture_descriptor.format.pixel_size();
+    let size = image.size();
+    let max = rect.max.min(size);
+    let extruded_max = (rect.max + padding).min(size);
+    let index = |x: u32, y: u32| (y * size.x + x) as usize * format_size;
+
+    let source_size = max - rect.min;
+    let source = |position: u32, min: u32, max: u32, size: u32| {
+        if wrap {
+            min + (position - max) % size
+        } else {
+            max - 1
+        }
+    };
+
+    for y in rect.min.y..max.y {
+        for x in max.x..extruded_max.x {
+            let source = index(source(x, rect.min.x, max.x, source_size.x), y);
+            image
+                .data
+                .copy_within(source..source + format_size, index(x, y));
+        }
+    }
+    for y in max.y..extruded_max.y {
+        let source_y = source(y, rect.min.y, max.y, source_size.y);
+        let row_begin = index(rect.min.x, source_y);
+        let row_end = index(extruded_max.x, source_y);
+        image
+            .data
+            .copy_within(row_begin..row_end, index(rect.min.x, y));
+    }
+}
+
+/// Moves the pixel at `(x, y)` to the position returned by `target`. Width and height are swapped.
+fn rotate(image: &Image, target: impl Fn(u32, u32, UVec2) -> (u32, u32)) -> Image {
+    let format_size = image.texture_descriptor.format.pixel_size();
+    let size = image.size();
+    let mut data = vec![0; image.data.len()];
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let (target_x, target_y) = target(x, y, size);
+            let source_index = (y * size.x + x) as usize * format_size;
+            let target_index = (target_y * size.y + target_x) as usize * format_size;
+            data[target_index..target_index + format_size]
+                .copy_from_slice(&image.data[source_index..source_index + format_size]);
+        }
+    }
+
+    Image::new(
+        Extent3d {
+            width: size.y,
+            height: size.x,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        image.texture_descriptor.format,
+        RenderAssetUsages::MAIN_WORLD,
+    )
+}
+
+/// Makes every pixel transparent whose color is within `tolerance` of `color_key` in every channel.
+///
+/// Returns `false` and leaves the image untouched if the format has no unsigned 8 bit alpha channel.
+pub(crate) fn apply_color_key(image: &mut Image, color_key: [u8; 3], tolerance: u8) -> bool {
+    let Some(alpha) = alpha_channel(image.texture_descriptor.format) else {
+        return false;
+    };
+    let color_key = match image.texture_descriptor.format {
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+            [color_key[2], color_key[1], color_key[0]]
+        }
+        _ => color_key,
+    };
+    let format_size = image.texture_descriptor.format.pixel_size();
+
+    for pixel in image.data.chunks_exact_mut(format_size) {
+        if pixel[..3]
+            .iter()
+            .zip(color_key)
+            .all(|(channel, key)| channel.abs_diff(key) <= tolerance)
+        {
+            pixel[alpha] = 0;
+        }
+    }
+
+    true
+}
+
+/// Detects the cells between full rows and columns of `gutter_color`, row by row.
+///
+/// Returns `None` if the format has no 8 bit color channels.
+pub(crate) fn color_grid_rects(
+    image: &Image,
+    gutter_color: [u8; 3],
+    tolerance: u8,
+) -> Option<Vec<URect>> {
+    alpha_channel(image.texture_descriptor.format)?;
+    let gutter_color = match image.texture_descriptor.format {
+        TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+            [gutter_color[2], gutter_color[1], gutter_color[0]]
+        }
+        _ => gutter_color,
+    };
+    let size = image.size();
+    let format_size = image.texture_descriptor.format.pixel_size();
+    let is_gutter = |x: u32, y: u32| {
+        let offset = (y * size.x + x) as usize * format_size;
+        image.data[offset..offset + 3]
+            .iter()
+            .zip(gutter_color)
+            .all(|(channel, gutter)| channel.abs_diff(gutter) <= tolerance)
+    };
+    let columns = cell_ranges(size.x, |x| (0..size.y).all(|y| is_gutter(x, y)));
+    let rows = cell_ranges(size.y, |y| (0..size.x).all(|x| is_gutter(x, y)));
+
+    Some(
+        rows.iter()
+            .flat_map(|&(min_y, max_y)| {
+                columns
+                    .iter()
+                    .map(move |&(min_x, max_x)| URect::new(min_x, min_y, max_x, max_y))
+            })
+            .collect(),
+    )
+}
+
+/// Ranges between the gutters along an axis of the given length.
+fn cell_ranges(len: u32, is_gutter: impl Fn(u32) -> bool) -> Vec<(u32, u32)> {
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for i in 0..len {
+        match (is_gutter(i), start) {
+            (true, Some(min)) => {
+                ranges.push((min, i));
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(min) = start {
+        ranges.push((min, len));
+    }
+
+    ranges
+}
+
+/// Multiplies the color channels of every pixel by its normalized alpha.
+///
+/// Returns `false` and leaves the image untouched if the format has no unsigned 8 bit alpha channel.
+pub(crate) fn premultiply_alpha(image: &mut Image) -> bool {
+    let Some(alpha) = alpha_channel(image.texture_descriptor.format) else {
+        return false;
+    };
+    let format_size = image.texture_descriptor.format.pixel_size();
+
+    for pixel in image.data.chunks_exact_mut(format_size) {
+        let a = pixel[alpha] as u16;
+        for channel in &mut pixel[..3] {
+            *channel = ((*channel as u16 * a + 127) / 255) as u8;
+        }
+    }
+
+    true
+}
+
+/// Snaps the alpha of every pixel to 0 below `threshold` and to 255 otherwise, the color channels are kept.
+///
+/// Returns `false` and leaves the image untouched if the format has no unsigned 8 bit alpha channel.
+pub(crate) fn snap_alpha(image: &mut Image, threshold: u8) -> bool {
+    let Some(alpha) = alpha_channel(image.texture_descriptor.format) else {
+        return false;
+    };
+    let format_size = image.texture_descriptor.format.pixel_size();
+
+    for pixel in image.data.chunks_exact_mut(format_size) {
+        pixel[alpha] = if pixel[alpha] < threshold { 0 } else { 255 };
+    }
+
+    true
+}
+
+/// Converts the image to `format`.
+///
+/// Converting [`TextureFormat::R8Unorm`] to a four channel 8 bit unorm format replicates the single channel
+/// according to `channel_mapping`, all other conversions are done by [`Image::convert`].
+/// Returns `None` if the conversion is not supported.
+pub(crate) fn convert(
+    image: &Image,
+    format: TextureFormat,
+    channel_mapping: TitanChannelMapping,
+) -> Option<Image> {
+    let source_format = image.texture_descriptor.format;
+    if source_format == format {
+        return Some(image.clone());
+    }
+
+    match (source_format, format) {
+        (
+            TextureFormat::R8Unorm,
+            TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb,
+        ) => {
+            let mut converted_image = image.clone();
+            converted_image.texture_descriptor.format = format;
+            converted_image.data = image
+                .data
+                .iter()
+                .flat_map(|&value| match channel_mapping {
+                    TitanChannelMapping::Luminance => [value, value, value, u8::MAX],
+                    TitanChannelMapping::Alpha => [u8::MAX, u8::MAX, u8::MAX, value],
+                })
+                .collect();
+            Some(converted_image)
+        }
+        _ => image.convert(format),
+    }
+}
+
+/// Appends a full mip chain to the image, where each level is box filtered from the previous one.
+/// Every layer of an array texture gets its own mip chain. The color channels of srgb formats are averaged
+/// in linear space, so the levels do not get darker.
+///
+/// Returns `false` and leaves the image untouched if the format is not an uncompressed 8 bit unorm format.
+pub(crate) fn generate_mipmaps(image: &mut Image) -> bool {
+    if !is_8_bit_unorm(image.texture_descriptor.format) {
+        return false;
+    }
+
+    let srgb = image.texture_descriptor.format.is_srgb();
+    let to_linear: [f32; 256] = std::array::from_fn(|value| srgb_to_linear(value as u8));
+    let channels = image.texture_descriptor.format.pixel_size();
+    let layer_size = image.size();
+    let mip_level_count = u32::BITS - layer_size.max_element().leading_zeros();
+    let layer_len = (layer_size.x * layer_size.y) as usize * channels;
+    let mut data = Vec::with_capacity(image.data.len() * 2);
+    for layer in image.data.chunks_exact(layer_len) {
+        let mut size = layer_size;
+        let mut level_begin = data.len();
+        data.extend_from_slice(layer);
+        for _ in 1..mip_level_count {
+            let next_size = (size / 2).max(UVec2::ONE);
+            let level_len = (size.x * size.y) as usize * channels;
+
+            let mut next_level =
+                Vec::with_capacity((next_size.x * next_size.y) as usize * channels);
+            let level = &data[level_begin..level_begin + level_len];
+            for y in 0..next_size.y {
+                for x in 0..next_size.x {
+                    for channel in 0..channels {
+                        let texels = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(dx, dy)| {
+                            let source_x = (2 * x + dx).min(size.x - 1);
+                            let source_y = (2 * y + dy).min(size.y - 1);
+                            level[(source_y * size.x + source_x) as usize * channels + channel]
+                        });
+                        /* Alpha is always linear */
+                        next_level.push(if srgb && channel != 3 {
+                            let sum: f32 =
+                                texels.map(|texel| to_linear[texel as usize]).iter().sum();
+                            linear_to_srgb(sum / 4.0)
+                        } else {
+                            let sum: u32 = texels.map(u32::from).iter().sum();
+                            ((sum + 2) / 4) as u8
+                        });
+                    }
+                }
+            }
+
+            level_begin += level_len;
+            data.extend(next_level);
+            size = next_size;
+        }
+    }
+    image.data = data;
+    image.texture_descriptor.mip_level_count = mip_level_count;
+
+    true
+}
+
+/// Converts an srgb encoded channel to linear space.
+fn srgb_to_linear(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linear channel to srgb encoding.
+fn linear_to_srgb(value: f32) -> u8 {
+    let value = if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    };
+    (value * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// Resamples the image to `size`.
+///
+/// Returns `None` for compressed formats. Bilinear filtering is only supported for uncompressed 8 bit unorm formats.
+pub(crate) fn scale(image: &Image, size: UVec2, filter: TitanScaleFilter) -> Option<Image> {
+    let format = image.texture_descriptor.format;
+    if format.is_compressed() || (filter == TitanScaleFilter::Bilinear && !is_8_bit_unorm(format)) {
+        return None;
+    }
+
+    let format_size = format.pixel_size();
+    let source_size = image.size();
+    let mut data = Vec::with_capacity((size.x * size.y) as usize * format_size);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            /* Position of the pixel center within the source image */
+            let source =
+                (Vec2::new(x as f32, y as f32) + 0.5) * source_size.as_vec2() / size.as_vec2();
+            match filter {
+                TitanScaleFilter::Nearest => {
+                    let source = source.as_uvec2().min(source_size - 1);
+                    let index = (source.y * source_size.x + source.x) as usize * format_size;
+                    data.extend_from_slice(&image.data[index..index + format_size]);
+                }
+                TitanScaleFilter::Bilinear => push_bilinear(&mut data, image, source),
+            }
+        }
+    }
+
+    Some(Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        RenderAssetUsages::MAIN_WORLD,
+    ))
+}
+
+/// Resamples the pixels within `rect` with bilinear filtering into a new image of the rounded size of `rect`,
+/// so that `rect` does not have to be aligned to whole pixels.
+///
+/// Returns `None` if `rect` exceeds the image or the texture format is not 8 bit unorm.
+pub(crate) fn extract_texture_from_subpixel_rect(image: &Image, rect: Rect) -> Option<Image> {
+    let format = image.texture_descriptor.format;
+    if rect.min.cmplt(Vec2::ZERO).any()
+        || rect.max.cmpgt(image.size().as_vec2()).any()
+        || !is_8_bit_unorm(format)
+    {
+        return None;
+    }
+
+    let size = rect.size().round().as_uvec2().max(UVec2::ONE);
+    let mut data = Vec::with_capacity((size.x * size.y) as usize * format.pixel_size());
+    for y in 0..size.y {
+        for x in 0..size.x {
+            /* Position of the pixel center within the source image */
+            let source =
+                rect.min + (Vec2::new(x as f32, y as f32) + 0.5) * rect.size() / size.as_vec2();
+            push_bilinear(&mut data, image, source);
+        }
+    }
+
+    Some(Image::new(
+        Extent3d {
+            width: size.x,
+            height: size.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        RenderAssetUsages::MAIN_WORLD,
+    ))
+}
+
+/// Appends the pixel at `position` of an 8 bit unorm image, interpolated between the four nearest pixel centers.
+fn push_bilinear(data: &mut Vec<u8>, image: &Image, position: Vec2) {
+    let format_size = image.texture_descriptor.format.pixel_size();
+    let size = image.size();
+    let source = (position - 0.5).max(Vec2::ZERO);
+    let min = source.as_uvec2().min(size - 1);
+    let max = (min + 1).min(size - 1);
+    let t = source - min.as_vec2();
+    let pixel = |x: u32, y: u32| (y * size.x + x) as usize * format_size;
+    for channel in 0..format_size {
+        let value = |x: u32, y: u32| image.data[pixel(x, y) + channel] as f32;
+        let top = value(min.x, min.y) * (1.0 - t.x) + value(max.x, min.y) * t.x;
+        let bottom = value(min.x, max.y) * (1.0 - t.x) + value(max.x, max.y) * t.x;
+        data.push((top * (1.0 - t.y) + bottom * t.y).round() as u8);
+    }
+}
+
+/// Whether every channel of the format is an 8 bit unorm.
+fn is_8_bit_unorm(format: TextureFormat) -> bool {
+    matches!(
+        format,
+        TextureFormat::R8Unorm
+            | TextureFormat::Rg8Unorm
+            | TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb
+    )
+}
+
+/// Byte offset of the alpha channel within a pixel for formats with an unsigned 8 bit alpha channel.
+///
+/// Signed formats are not supported, their bytes are two's complement instead of plain alpha values.
+fn alpha_channel(format: TextureFormat) -> Option<usize> {
+    match format {
+        TextureFormat::Rgba8Unorm
+        | TextureFormat::Rgba8UnormSrgb
+        | TextureFormat::Rgba8Uint
+        | TextureFormat::Bgra8Unorm
+        | TextureFormat::Bgra8UnormSrgb => Some(3),
+        _ => None,
+    }
+}
@@ -0,0 +1,86 @@
+//! This module parses the JSON data file of an Aseprite sprite sheet export.
+//!
+//! Frames are read just like a TexturePacker JSON file, every frame tag becomes a named animation.
+
+use serde::Deserialize;
+
+use crate::{
+    asset_loader::ping_pong,
+    manifest::{TitanAnimation, TitanFrame},
+};
+
+use super::{
+    texture_packer::{imported_sprites, TexturePackerFrames},
+    ImportedAtlas,
+};
+
+#[derive(Debug, Deserialize)]
+struct AsepriteFile {
+    frames: TexturePackerFrames,
+    meta: AsepriteMeta,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AsepriteMeta {
+    image: String,
+    #[serde(default)]
+    frame_tags: Vec<AsepriteFrameTag>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AsepriteFrameTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: AsepriteDirection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum AsepriteDirection {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
+
+/// Parses an Aseprite JSON file. Every frame tag becomes an animation with the durations of its frames.
+pub(crate) fn parse(bytes: &[u8]) -> Result<ImportedAtlas, serde_json::Error> {
+    let aseprite_file = serde_json::from_slice::<AsepriteFile>(bytes)?;
+    let sprites = imported_sprites(aseprite_file.frames)?;
+    let animations = aseprite_file
+        .meta
+        .frame_tags
+        .into_iter()
+        .map(|frame_tag| TitanAnimation {
+            frames: frames(&frame_tag)
+                .into_iter()
+                .map(TitanFrame::Index)
+                .collect(),
+            name: frame_tag.name,
+            duration_ms: None,
+            ..Default::default()
+        })
+        .collect();
+
+    Ok(ImportedAtlas {
+        image: aseprite_file.meta.image,
+        sprites,
+        animations,
+    })
+}
+
+/// Frame indices of a frame tag in playback order. Ping-pong does not repeat the frames at either end.
+fn frames(frame_tag: &AsepriteFrameTag) -> Vec<usize> {
+    let forward = frame_tag.from..=frame_tag.to;
+
+    match frame_tag.direction {
+        AsepriteDirection::Forward => forward.collect(),
+        AsepriteDirection::Reverse => forward.rev().collect(),
+        AsepriteDirection::Pingpong => ping_pong(forward.collect()),
+        AsepriteDirection::PingpongReverse => ping_pong(forward.rev().collect()),
+    }
+}
@@ -0,0 +1,193 @@
+//! This module parses the LibGDX TextureAtlas text format.
+//!
+//! Both the legacy format (`xy`, `size`, `orig`, `offset`) and the current format (`bounds`, `offsets`) are supported.
+//! Only atlases with a single page can be imported.
+
+use bevy::{
+    math::{URect, UVec2},
+    utils::HashMap,
+};
+
+use crate::{
+    asset_loader::LibGdxAtlasError,
+    manifest::{TitanAnimation, TitanFrame},
+    metadata::SpriteTrim,
+};
+
+use super::{ImportedAtlas, ImportedRotation, ImportedSprite};
+
+/// A single region. `offset` is measured from the bottom left corner of the original image.
+#[derive(Debug, Default)]
+struct Region {
+    name: String,
+    position: UVec2,
+    size: UVec2,
+    original_size: Option<UVec2>,
+    offset: UVec2,
+    index: i32,
+    rotated: bool,
+}
+
+/// Parses a LibGDX atlas file. Regions with an `index` are named with their index appended, e.g. `run_0`,
+/// and make up an animation named after the region in the order of their index.
+pub(crate) fn parse(text: &str) -> Result<ImportedAtlas, LibGdxAtlasError> {
+    let mut page = None;
+    let mut expect_page = true;
+    let mut regions: Vec<Region> = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let error = |message: &str| LibGdxAtlasError(line_number, message.to_string());
+        let line = line.trim();
+
+        /* Pages are separated by an empty line */
+        if line.is_empty() {
+            expect_page = true;
+            continue;
+        }
+
+        match line.split_once(':') {
+            Some((key, value)) => {
+                /* Fields before the first region belong to the page and are not needed */
+                if expect_page {
+                    return Err(error("Expected a page"));
+                }
+                let Some(region) = regions.last_mut() else {
+                    continue;
+                };
+                let values = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|value| !value.is_empty())
+                    .collect::<Vec<_>>();
+                let numbers = || {
+                    values
+                        .iter()
+                        .map(|value| value.parse::<i64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|_| error(&format!("Invalid value for {}", key.trim())))
+                };
+                let unsigned = |value: i64| {
+                    u32::try_from(value)
+                        .map_err(|_| error(&format!("Negative value for {}", key.trim())))
+                };
+                let vec2 = |numbers: &[i64]| match numbers {
+                    [x, y, ..] => Ok(UVec2::new(unsigned(*x)?, unsigned(*y)?)),
+                    _ => Err(error(&format!("Expected two values for {}", key.trim()))),
+                };
+
+                match key.trim() {
+                    "rotate" => region.rotated = matches!(value.trim(), "true" | "90"),
+                    "xy" => region.position = vec2(&numbers()?)?,
+                    "size" => region.size = vec2(&numbers()?)?,
+                    "orig" => region.original_size = Some(vec2(&numbers()?)?),
+                    "offset" => region.offset = vec2(&numbers()?)?,
+                    "bounds" | "offsets" => {
+                        let numbers = numbers()?;
+                        if numbers.len() != 4 {
+                            return Err(error(&format!("Expected four values for {}", key.trim())));
+                        }
+                        if key.trim() == "bounds" {
+                            region.position = vec2(&numbers[..2])?;
+                            region.size = vec2(&numbers[2..])?;
+                        } else {
+                            region.offset = vec2(&numbers[..2])?;
+                            region.original_size = Some(vec2(&numbers[2..])?);
+                        }
+                    }
+                    "index" => {
+                        region.index = value
+                            .trim()
+                            .parse()
+                            .map_err(|_| error("Invalid value for index"))?;
+                    }
+                    _ => {}
+                }
+            }
+            None if expect_page => {
+                if page.is_some() {
+                    return Err(error("Atlases with multiple pages are not supported"));
+                }
+                page = Some(line.to_string());
+                expect_page = false;
+            }
+            None => regions.push(Region {
+                name: line.to_string(),
+                index: -1,
+                ..Default::default()
+            }),
+        }
+    }
+    let image = page.ok_or_else(|| LibGdxAtlasError(0, "No page found".to_string()))?;
+
+    let mut animations: Vec<(String, Vec<(i32, usize)>)> = Vec::new();
+    let mut animation_indices = HashMap::new();
+    let sprites = regions
+        .into_iter()
+        .enumerate()
+        .map(|(sprite_index, region)| {
+            let name = if region.index >= 0 {
+                let animation_index =
+                    *animation_indices
+                        .entry(region.name.clone())
+                        .or_insert_with(|| {
+                            animations.push((region.name.clone(), Vec::new()));
+                            animations.len() - 1
+                        });
+                animations[animation_index]
+                    .1
+                    .push((region.index, sprite_index));
+                format!("{}_{}", region.name, region.index)
+            } else {
+                region.name.clone()
+            };
+            imported_sprite(name, &region)
+        })
+        .collect();
+    let animations = animations
+        .into_iter()
+        .map(|(name, mut frames)| {
+            frames.sort_by_key(|(index, _)| *index);
+            TitanAnimation {
+                name,
+                frames: frames
+                    .into_iter()
+                    .map(|(_, sprite_index)| TitanFrame::Index(sprite_index))
+                    .collect(),
+                duration_ms: None,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Ok(ImportedAtlas {
+        image,
+        sprites,
+        animations,
+    })
+}
+
+fn imported_sprite(name: String, region: &Region) -> ImportedSprite {
+    let original_size = region.original_size.unwrap_or(region.size);
+    /* LibGDX measures the offset from the bottom */
+    let offset = UVec2::new(
+        region.offset.x,
+        original_size
+            .y
+            .saturating_sub(region.size.y + region.offset.y),
+    );
+
+    ImportedSprite {
+        name: Some(name),
+        rect: URect::from_corners(region.position, region.position + region.size),
+        rotation: if region.rotated {
+            ImportedRotation::Counterclockwise
+        } else {
+            ImportedRotation::None
+        },
+        trim: SpriteTrim {
+            original_size,
+            offset,
+        },
+        duration: None,
+    }
+}
@@ -0,0 +1,215 @@
+//! This module handles atlases that were already packed by another tool.
+//!
+//! Every importer parses its file format into an [`ImportedAtlas`]. The referenced atlas image is used as is,
+//! only atlases with rotated sprites are packed again to undo the rotation.
+
+use std::{path::Path, time::Duration};
+
+use bevy::{
+    asset::{AssetPath, LoadContext},
+    image::Image,
+    math::{URect, UVec2},
+    render::render_asset::RenderAssetUsages,
+    sprite::TextureAtlasLayout,
+    utils::HashMap,
+};
+
+use crate::{
+    asset_loader::{
+        add_labeled_assets, build_animations, build_texture_atlas, decode_images,
+        DuplicateNameError, InvalidRectError, PackedAtlas, SpriteSheetLoaderError,
+        SpriteSheetLoaderSettings, TextureAtlas, TitanLoadProgress, DEFAULT_ANCHOR,
+    },
+    image_utils::{extract_texture_from_rect, rotate_clockwise, rotate_counterclockwise},
+    manifest::{Checksum, TitanAnimation, TitanConfiguration},
+    metadata::{AnimationFrame, SpriteGroup, SpriteSource, SpriteTrim},
+};
+
+pub(crate) mod aseprite;
+pub(crate) mod libgdx;
+pub(crate) mod texture_packer;
+
+/// An atlas that was packed by another tool.
+pub(crate) struct ImportedAtlas {
+    /// Path of the atlas image, relative to the imported file.
+    pub(crate) image: String,
+    pub(crate) sprites: Vec<ImportedSprite>,
+    pub(crate) animations: Vec<TitanAnimation>,
+}
+
+/// A single sprite of an [`ImportedAtlas`].
+pub(crate) struct ImportedSprite {
+    pub(crate) name: Option<String>,
+    /// Rect within the atlas image. The size is the one of the unrotated sprite.
+    pub(crate) rect: URect,
+    pub(crate) rotation: ImportedRotation,
+    pub(crate) trim: SpriteTrim,
+    pub(crate) duration: Option<Duration>,
+}
+
+/// How a sprite is rotated within the atlas image.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ImportedRotation {
+    #[default]
+    None,
+    /// Rotated by 90 degrees clockwise.
+    Clockwise,
+    /// Rotated by 90 degrees counterclockwise.
+    Counterclockwise,
+}
+
+/// Reads the atlas image of `imported_atlas` and adds all labeled assets just like for a titan ron file.
+pub(crate) async fn load_imported_atlas(
+    imported_atlas: ImportedAtlas,
+    load_context: &mut LoadContext<'_>,
+    settings: &SpriteSheetLoaderSettings,
+    mut checksum: Checksum,
+    progress: &TitanLoadProgress,
+) -> Result<TextureAtlas, SpriteSheetLoaderError> {
+    let image_path = load_context
+        .path()
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(&imported_atlas.image);
+    let image_path = image_path.to_string_lossy().into_owned();
+    let bytes = load_context
+        .read_asset_bytes(AssetPath::from_path(Path::new(&image_path)))
+        .await?;
+    checksum.update(image_path.as_bytes());
+    checksum.update(&bytes);
+    let texture = decode_images(load_context, &[(&image_path, bytes)], false, progress)
+        .await
+        .remove(0)?;
+
+    let mut packed_atlas = pack_imported_atlas(imported_atlas, texture, &image_path)?;
+    packed_atlas.checksum = checksum.finish();
+    if settings.layout_only {
+        packed_atlas.drop_pixels();
+    }
+
+    Ok(add_labeled_assets(
+        load_context,
+        packed_atlas,
+        settings.labels(),
+    ))
+}
+
+/// Uses the rects of `imported_atlas` as layout of `texture`.
+fn pack_imported_atlas(
+    imported_atlas: ImportedAtlas,
+    mut texture: Image,
+    path: &str,
+) -> Result<PackedAtlas, SpriteSheetLoaderError> {
+    let sprites = imported_atlas.sprites;
+    let atlas_rect = |sprite: &ImportedSprite| {
+        if sprite.rotation != ImportedRotation::None {
+            URect::from_corners(
+                sprite.rect.min,
+                sprite.rect.min + UVec2::new(sprite.rect.height(), sprite.rect.width()),
+            )
+        } else {
+            sprite.rect
+        }
+    };
+    for (index, sprite) in sprites.iter().enumerate() {
+        let rect = atlas_rect(sprite);
+        if rect.max.cmpgt(texture.size()).any() {
+            return Err(InvalidRectError(rect.min, rect.max, path.to_string(), index).into());
+        }
+    }
+
+    let layout = if sprites
+        .iter()
+        .any(|sprite| sprite.rotation != ImportedRotation::None)
+    {
+        /* Undo the rotation by extracting and packing every sprite again */
+        let images = sprites
+            .iter()
+            .filter_map(|sprite| {
+                let image = extract_texture_from_rect(&texture, atlas_rect(sprite))?;
+                Some(match sprite.rotation {
+                    ImportedRotation::None => image,
+                    ImportedRotation::Clockwise => rotate_counterclockwise(&image),
+                    ImportedRotation::Counterclockwise => rotate_clockwise(&image),
+                })
+            })
+            .collect::<Vec<_>>();
+        /* Unrotated sprites fit into twice the size of the original atlas */
+        let configuration = TitanConfiguration {
+            format: texture.texture_descriptor.format,
+            max_size: (texture.size() * 2).max(TitanConfiguration::default().max_size),
+            ..Default::default()
+        };
+        let (layout, packed_texture) = build_texture_atlas(&configuration, &images)?;
+        texture = packed_texture;
+
+        layout
+    } else {
+        texture.asset_usage = RenderAssetUsages::MAIN_WORLD | RenderAssetUsages::RENDER_WORLD;
+        let mut layout = TextureAtlasLayout::new_empty(texture.size());
+        for sprite in &sprites {
+            layout.add_texture(sprite.rect);
+        }
+
+        layout
+    };
+
+    let mut names = HashMap::new();
+    for (index, sprite) in sprites.iter().enumerate() {
+        if let Some(name) = &sprite.name {
+            if names.insert(name.clone(), index).is_some() {
+                return Err(DuplicateNameError(name.clone()).into());
+            }
+        }
+    }
+    let trims = sprites.iter().map(|sprite| sprite.trim).collect();
+    let rotations = vec![false; sprites.len()];
+    let anchors = vec![DEFAULT_ANCHOR; sprites.len()];
+    let slices = vec![None; sprites.len()];
+    let tileables = vec![false; sprites.len()];
+    let source_rects = sprites
+        .iter()
+        .map(|sprite| SpriteSource {
+            path: path.to_string(),
+            rect: atlas_rect(sprite),
+            transformed: false,
+        })
+        .collect();
+    let durations = sprites
+        .iter()
+        .map(|sprite| sprite.duration)
+        .collect::<Vec<_>>();
+    let animation = durations
+        .iter()
+        .enumerate()
+        .filter_map(|(index, duration)| duration.map(|duration| AnimationFrame { index, duration }))
+        .collect();
+    let animations = build_animations(imported_atlas.animations, &durations, &names)?;
+
+    Ok(PackedAtlas {
+        page_indices: vec![0; layout.len()],
+        layout,
+        texture,
+        additional_pages: Vec::new(),
+        names,
+        trims,
+        animation,
+        animations,
+        rotations,
+        anchors,
+        slices,
+        tileables,
+        source_rects,
+        sources: HashMap::from([(
+            path.to_string(),
+            std::iter::once(0..sprites.len()).collect(),
+        )]),
+        groups: vec![SpriteGroup {
+            name: None,
+            indices: 0..sprites.len(),
+        }],
+        version: None,
+        checksum: 0,
+        default_index: None,
+    })
+}
@@ -0,0 +1,126 @@
+//! This module parses the JSON data file of TexturePacker in both the Hash and the Array layout.
+
+use std::time::Duration;
+
+use bevy::math::{URect, UVec2};
+use serde::Deserialize;
+
+use crate::metadata::SpriteTrim;
+
+use super::{ImportedAtlas, ImportedRotation, ImportedSprite};
+
+#[derive(Debug, Deserialize)]
+struct TexturePackerFile {
+    frames: TexturePackerFrames,
+    meta: TexturePackerMeta,
+}
+
+/// Frames are either keyed by their name or a list with a `filename` each.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(super) enum TexturePackerFrames {
+    Hash(serde_json::Map<String, serde_json::Value>),
+    Array(Vec<TexturePackerNamedFrame>),
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct TexturePackerNamedFrame {
+    filename: String,
+    #[serde(flatten)]
+    frame: TexturePackerFrame,
+}
+
+#[derive(Debug, Deserialize)]
+struct TexturePackerMeta {
+    image: String,
+}
+
+/// A single frame. `duration` in milliseconds is only written by Aseprite.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TexturePackerFrame {
+    frame: TexturePackerRect,
+    #[serde(default)]
+    rotated: bool,
+    #[serde(default)]
+    trimmed: bool,
+    sprite_source_size: Option<TexturePackerRect>,
+    source_size: Option<TexturePackerSize>,
+    duration: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TexturePackerRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct TexturePackerSize {
+    w: u32,
+    h: u32,
+}
+
+/// Parses a TexturePacker JSON file. Sprites keep the order of the file and are named by their key or `filename`.
+pub(crate) fn parse(bytes: &[u8]) -> Result<ImportedAtlas, serde_json::Error> {
+    let texture_packer_file = serde_json::from_slice::<TexturePackerFile>(bytes)?;
+    let sprites = imported_sprites(texture_packer_file.frames)?;
+
+    Ok(ImportedAtlas {
+        image: texture_packer_file.meta.image,
+        sprites,
+        animations: Vec::new(),
+    })
+}
+
+pub(super) fn imported_sprites(
+    frames: TexturePackerFrames,
+) -> Result<Vec<ImportedSprite>, serde_json::Error> {
+    match frames {
+        TexturePackerFrames::Hash(frames) => frames
+            .into_iter()
+            .map(|(name, frame)| {
+                let frame = serde_json::from_value::<TexturePackerFrame>(frame)?;
+                Ok(imported_sprite(Some(name), frame))
+            })
+            .collect(),
+        TexturePackerFrames::Array(frames) => Ok(frames
+            .into_iter()
+            .map(|frame| imported_sprite(Some(frame.filename), frame.frame))
+            .collect()),
+    }
+}
+
+fn imported_sprite(name: Option<String>, frame: TexturePackerFrame) -> ImportedSprite {
+    let rect = frame.frame;
+    let size = UVec2::new(rect.w, rect.h);
+    let trim = match (frame.trimmed, frame.sprite_source_size, frame.source_size) {
+        (true, Some(sprite_source_size), Some(source_size)) => SpriteTrim {
+            original_size: UVec2::new(source_size.w, source_size.h),
+            offset: UVec2::new(sprite_source_size.x, sprite_source_size.y),
+        },
+        _ => SpriteTrim {
+            original_size: size,
+            offset: UVec2::ZERO,
+        },
+    };
+
+    ImportedSprite {
+        name,
+        rect: URect::from_corners(
+            UVec2::new(rect.x, rect.y),
+            UVec2::new(rect.x, rect.y) + size,
+        ),
+        rotation: if frame.rotated {
+            ImportedRotation::Clockwise
+        } else {
+            ImportedRotation::None
+        },
+        trim,
+        duration: frame
+            .duration
+            .map(|duration| Duration::from_millis(duration.into())),
+    }
+}
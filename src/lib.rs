@@ -8,15 +8,47 @@ use bevy::{
 };
 
 pub mod asset_loader;
-mod serde;
+pub mod builder;
+pub mod export;
+mod image_utils;
+mod import;
+pub mod manifest;
+pub mod metadata;
 
 /// Adds support for spritesheet manifest files loading to the app.
 pub struct SpriteSheetLoaderPlugin;
 
 impl Plugin for SpriteSheetLoaderPlugin {
     fn build(&self, app: &mut App) {
-        app.register_type::<crate::asset_loader::TextureAtlas>();
+        app.register_type::<crate::asset_loader::TextureAtlas>()
+            .register_type::<crate::metadata::TextureAtlasNames>()
+            .register_type::<crate::metadata::TextureAtlasTrims>()
+            .register_type::<crate::metadata::TextureAtlasAnimation>()
+            .register_type::<crate::metadata::TextureAtlasAnimations>()
+            .register_type::<crate::metadata::TextureAtlasRotations>()
+            .register_type::<crate::metadata::TextureAtlasAnchors>()
+            .register_type::<crate::metadata::TextureAtlasSlices>()
+            .register_type::<crate::metadata::TextureAtlasTileables>()
+            .register_type::<crate::metadata::TextureAtlasSourceRects>()
+            .register_type::<crate::metadata::TextureAtlasSources>()
+            .register_type::<crate::metadata::TextureAtlasGroups>()
+            .register_type::<crate::metadata::TextureAtlasStats>()
+            .register_type::<crate::metadata::TextureAtlasUvs>();
         app.init_asset::<asset_loader::TextureAtlas>()
+            .init_asset::<metadata::TextureAtlasNames>()
+            .init_asset::<metadata::TextureAtlasTrims>()
+            .init_asset::<metadata::TextureAtlasAnimation>()
+            .init_asset::<metadata::TextureAtlasAnimations>()
+            .init_asset::<metadata::TextureAtlasRotations>()
+            .init_asset::<metadata::TextureAtlasAnchors>()
+            .init_asset::<metadata::TextureAtlasSlices>()
+            .init_asset::<metadata::TextureAtlasTileables>()
+            .init_asset::<metadata::TextureAtlasSourceRects>()
+            .init_asset::<metadata::TextureAtlasSources>()
+            .init_asset::<metadata::TextureAtlasGroups>()
+            .init_asset::<metadata::TextureAtlasStats>()
+            .init_asset::<metadata::TextureAtlasUvs>()
+            .init_resource::<asset_loader::TitanLoadProgress>()
             .init_asset_loader::<asset_loader::SpriteSheetLoader>();
     }
 }
@@ -24,6 +56,27 @@ impl Plugin for SpriteSheetLoaderPlugin {
 /// `use bevy_titan::prelude::*;` to import common components and plugins.
 pub mod prelude {
     pub use crate::asset_loader::SpriteSheetLoaderError;
+    pub use crate::asset_loader::SpriteSheetLoaderSettings;
     pub use crate::asset_loader::TextureAtlas;
+    pub use crate::asset_loader::TitanLoadProgress;
+    pub use crate::builder::TitanBuilder;
+    pub use crate::manifest::Titan;
+    pub use crate::manifest::TitanConfiguration;
+    pub use crate::manifest::TitanEntry;
+    pub use crate::manifest::TitanSpriteSheet;
+    pub use crate::metadata::LayoutSnapshot;
+    pub use crate::metadata::TextureAtlasAnchors;
+    pub use crate::metadata::TextureAtlasAnimation;
+    pub use crate::metadata::TextureAtlasAnimations;
+    pub use crate::metadata::TextureAtlasGroups;
+    pub use crate::metadata::TextureAtlasNames;
+    pub use crate::metadata::TextureAtlasRotations;
+    pub use crate::metadata::TextureAtlasSlices;
+    pub use crate::metadata::TextureAtlasSourceRects;
+    pub use crate::metadata::TextureAtlasSources;
+    pub use crate::metadata::TextureAtlasStats;
+    pub use crate::metadata::TextureAtlasTileables;
+    pub use crate::metadata::TextureAtlasTrims;
+    pub use crate::metadata::TextureAtlasUvs;
     pub use crate::SpriteSheetLoaderPlugin;
 }
@@ -0,0 +1,1196 @@
+//! This module defines all types of a titan ron file.
+//!
+//! A [`Titan`] can be deserialized from a titan ron file, constructed programmatically and serialized again.
+//! See the [file format specification](https://github.com/KirmesBude/bevy_titan/blob/main/docs/FileFormatSpecification.md)
+//! for a description of every field.
+
+use std::path::Path;
+
+use bevy::{
+    image::{ImageSampler, ImageSamplerDescriptor},
+    math::{Rect, URect, UVec2, Vec2},
+    render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{TextureFormat, TextureUsages},
+    },
+    sprite::BorderRect,
+    utils::HashSet,
+};
+use serde::{
+    de::{
+        value::{MapAccessDeserializer, SeqAccessDeserializer},
+        DeserializeOwned, Error, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+    },
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::metadata::AnimationPlayback;
+
+use crate::asset_loader::SpriteSheetLoaderError;
+
+/// Root of a titan ron file.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Titan {
+    /// Free-form version of the titan ron file, available as `version` of the loaded
+    /// [`TextureAtlas`](crate::asset_loader::TextureAtlas).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Path of another titan ron file whose configuration is used for every field that is not set in this one.
+    #[serde(default)]
+    pub extends: Option<String>,
+    /// Configuration of the combined texture atlas.
+    #[serde(default)]
+    pub configuration: TitanConfiguration,
+    /// All image assets that are packed into the combined texture atlas.
+    ///
+    /// A single image split into a uniform grid can also be written as `Grid(path: "sheet.png", tile_size: (24, 24))`.
+    #[serde(deserialize_with = "deserialize_textures")]
+    pub textures: Vec<TitanEntry>,
+    /// Named animations referencing sprites by their index.
+    #[serde(default)]
+    pub animations: Vec<TitanAnimation>,
+}
+
+/// Configuration of the combined texture atlas.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TitanConfiguration {
+    /// Starting size of the combined texture atlas for the packing process.
+    #[serde(default = "default_initial_size")]
+    pub initial_size: UVec2,
+    /// Maximum size that the combined texture atlas is allowed to grow to.
+    #[serde(default = "default_max_size")]
+    pub max_size: UVec2,
+    /// Texture format of the combined texture atlas.
+    #[serde(
+        default = "default_format",
+        deserialize_with = "deserialize_texture_format"
+    )]
+    pub format: TextureFormat,
+    /// Convert sprites with a different texture format to `format`.
+    #[serde(default = "default_auto_format_conversion")]
+    pub auto_format_conversion: bool,
+    /// What to do with sprites whose texture format differs from `format`.
+    #[serde(default)]
+    pub on_format_mismatch: TitanFormatMismatch,
+    /// Padding between the sprites in the combined texture atlas.
+    #[serde(default = "default_padding")]
+    pub padding: UVec2,
+    /// Always run the packing algorithm, even for a single sprite.
+    #[serde(default = "default_always_pack")]
+    pub always_pack: bool,
+    /// Extract and pack all sprites. If false, the rects of a single entry are used as layout of its image as is.
+    #[serde(default = "default_repack")]
+    pub repack: bool,
+    /// Crop every sprite to its non-transparent pixels.
+    #[serde(default)]
+    pub trim: bool,
+    /// Pack identical sprites only once.
+    #[serde(default)]
+    pub deduplicate: bool,
+    /// Generate a full mip chain for the combined texture atlas.
+    #[serde(default)]
+    pub generate_mipmaps: bool,
+    /// Sampler of the combined texture atlas.
+    #[serde(default)]
+    pub sampler: TitanSampler,
+    /// RGB color that is made fully transparent in every sprite.
+    #[serde(default)]
+    pub color_key: Option<[u8; 3]>,
+    /// Maximum difference per channel for a color to still match `color_key`.
+    #[serde(default)]
+    pub color_key_tolerance: u8,
+    /// Multiply the color of every sprite by its alpha.
+    #[serde(default)]
+    pub premultiply_alpha: bool,
+    /// Snap the alpha of every sprite to 0 below this and to 255 otherwise, e.g. for crisp edges of scaled pixel art.
+    #[serde(default)]
+    pub alpha_threshold: Option<u8>,
+    /// Allow tall sprites to be rotated by 90 degrees clockwise if that results in a smaller texture atlas.
+    #[serde(default)]
+    pub allow_rotation: bool,
+    /// Clamp rects that exceed their image instead of failing.
+    #[serde(default)]
+    pub clamp_rects: bool,
+    /// Corner of the image that the positions of rects and offsets are measured from.
+    #[serde(default)]
+    pub coordinate_origin: TitanCoordinateOrigin,
+    /// Log every pair of overlapping rects of heterogeneous sprite sheets within the same image.
+    #[serde(default = "default_warn_on_overlap")]
+    pub warn_on_overlap: bool,
+    /// Fill the padding to the right and below every sprite with its edge pixels instead of transparent ones.
+    #[serde(default)]
+    pub extrude_edges: bool,
+    /// Whether the sprites are packed into a texture atlas or stacked into an array texture.
+    #[serde(default)]
+    pub output: TitanOutput,
+    /// Order in which the sprites are handed to the packing algorithm.
+    #[serde(default)]
+    pub sort_order: TitanSortOrder,
+    /// Start another page of the same size whenever the sprites do not fit into `max_size`.
+    #[serde(default)]
+    pub allow_multiple_pages: bool,
+    /// Prepended to the `path` of every entry, e.g. to retarget all images to another directory at once.
+    #[serde(default)]
+    pub base_path: String,
+    /// Skip all entries of an image that does not exist, instead of failing. The indices of all following
+    /// sprites shift accordingly.
+    #[serde(default)]
+    pub ignore_missing: bool,
+    /// Produce an empty texture atlas with a single transparent pixel instead of failing if there are no entries.
+    #[serde(default)]
+    pub allow_empty: bool,
+    /// Where the combined texture atlas is kept, e.g. also in the main world to read it back after it was uploaded.
+    #[serde(default)]
+    pub render_asset_usages: RenderAssetUsages,
+    /// Usages of every page on the GPU, e.g. with `STORAGE_BINDING` to process it in a compute shader.
+    #[serde(
+        default = "default_texture_usages",
+        serialize_with = "serialize_texture_usages",
+        deserialize_with = "deserialize_texture_usages"
+    )]
+    pub texture_usages: TextureUsages,
+    /// Index of the sprite that [`TextureAtlas::clamp_index`](crate::asset_loader::TextureAtlas::clamp_index)
+    /// falls back to for indices out of range.
+    #[serde(default)]
+    pub default_index: Option<usize>,
+    /// Position of every packed sprite and the size of the combined texture atlas are rounded up to
+    /// a multiple of this, e.g. 4 for block compression.
+    #[serde(default = "default_alignment")]
+    pub alignment: u32,
+    /// Round the size of every page up to the next power of two. The extra space is transparent.
+    #[serde(default)]
+    pub power_of_two: bool,
+    /// Maximum number of sprites.
+    #[serde(default = "default_max_frames")]
+    pub max_frames: u64,
+    /// Maximum number of pixels of all sprites combined.
+    #[serde(default = "default_max_total_area")]
+    pub max_total_area: u64,
+}
+
+impl Default for TitanConfiguration {
+    fn default() -> Self {
+        Self {
+            initial_size: default_initial_size(),
+            max_size: default_max_size(),
+            format: default_format(),
+            auto_format_conversion: default_auto_format_conversion(),
+            on_format_mismatch: TitanFormatMismatch::default(),
+            padding: default_padding(),
+            always_pack: default_always_pack(),
+            repack: default_repack(),
+            trim: false,
+            deduplicate: false,
+            generate_mipmaps: false,
+            sampler: TitanSampler::default(),
+            color_key: None,
+            color_key_tolerance: 0,
+            premultiply_alpha: false,
+            alpha_threshold: None,
+            allow_rotation: false,
+            clamp_rects: false,
+            coordinate_origin: TitanCoordinateOrigin::default(),
+            warn_on_overlap: default_warn_on_overlap(),
+            extrude_edges: false,
+            output: TitanOutput::default(),
+            sort_order: TitanSortOrder::default(),
+            allow_multiple_pages: false,
+            base_path: String::new(),
+            ignore_missing: false,
+            allow_empty: false,
+            render_asset_usages: RenderAssetUsages::default(),
+            texture_usages: default_texture_usages(),
+            default_index: None,
+            alignment: default_alignment(),
+            power_of_two: false,
+            max_frames: default_max_frames(),
+            max_total_area: default_max_total_area(),
+        }
+    }
+}
+
+impl TitanConfiguration {
+    /// Replaces every field named in `fields` with the one of `other`.
+    fn override_fields(&mut self, other: &Self, fields: &HashSet<String>) {
+        for field in fields {
+            self.override_field(other, field);
+        }
+    }
+
+    /// Replaces the field named `field` with the one of `other`. Returns false if there is no such field.
+    /// New fields have to be added here as well.
+    pub(crate) fn override_field(&mut self, other: &Self, field: &str) -> bool {
+        match field {
+            "initial_size" => self.initial_size = other.initial_size,
+            "max_size" => self.max_size = other.max_size,
+            "format" => self.format = other.format,
+            "auto_format_conversion" => self.auto_format_conversion = other.auto_format_conversion,
+            "on_format_mismatch" => self.on_format_mismatch = other.on_format_mismatch,
+            "padding" => self.padding = other.padding,
+            "always_pack" => self.always_pack = other.always_pack,
+            "repack" => self.repack = other.repack,
+            "trim" => self.trim = other.trim,
+            "deduplicate" => self.deduplicate = other.deduplicate,
+            "generate_mipmaps" => self.generate_mipmaps = other.generate_mipmaps,
+            "sampler" => self.sampler = other.sampler.clone(),
+            "color_key" => self.color_key = other.color_key,
+            "color_key_tolerance" => self.color_key_tolerance = other.color_key_tolerance,
+            "premultiply_alpha" => self.premultiply_alpha = other.premultiply_alpha,
+            "alpha_threshold" => self.alpha_threshold = other.alpha_threshold,
+            "allow_rotation" => self.allow_rotation = other.allow_rotation,
+            "clamp_rects" => self.clamp_rects = other.clamp_rects,
+            "coordinate_origin" => self.coordinate_origin = other.coordinate_origin,
+            "warn_on_overlap" => self.warn_on_overlap = other.warn_on_overlap,
+            "extrude_edges" => self.extrude_edges = other.extrude_edges,
+            "output" => self.output = other.output,
+            "sort_order" => self.sort_order = other.sort_order,
+            "allow_multiple_pages" => self.allow_multiple_pages = other.allow_multiple_pages,
+            "base_path" => self.base_path = other.base_path.clone(),
+            "ignore_missing" => self.ignore_missing = other.ignore_missing,
+            "allow_empty" => self.allow_empty = other.allow_empty,
+            "render_asset_usages" => self.render_asset_usages = other.render_asset_usages,
+            "texture_usages" => self.texture_usages = other.texture_usages,
+            "default_index" => self.default_index = other.default_index,
+            "alignment" => self.alignment = other.alignment,
+            "power_of_two" => self.power_of_two = other.power_of_two,
+            "max_frames" => self.max_frames = other.max_frames,
+            "max_total_area" => self.max_total_area = other.max_total_area,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+/// Syntax of a titan file, either ron or json.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TitanSyntax {
+    Ron,
+    Json,
+}
+
+impl TitanSyntax {
+    /// Files ending with `.json` are json, everything else is ron.
+    pub(crate) fn from_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(extension) if extension == "json" => Self::Json,
+            _ => Self::Ron,
+        }
+    }
+
+    pub(crate) fn deserialize<T: DeserializeOwned>(
+        self,
+        bytes: &[u8],
+    ) -> Result<T, SpriteSheetLoaderError> {
+        Ok(match self {
+            Self::Ron => ron::de::from_bytes(bytes)?,
+            Self::Json => serde_json::from_slice(bytes)?,
+        })
+    }
+}
+
+/// The parts of a titan file that are used when it is extended. All other fields are ignored,
+/// so the extended file does not need any `textures`.
+#[derive(Deserialize)]
+struct TitanBase {
+    #[serde(default)]
+    extends: Option<String>,
+    #[serde(default)]
+    configuration: TitanConfiguration,
+}
+
+/// Shorthand for `textures` that consist of a single image split into a uniform grid.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TitanGridShorthand {
+    path: String,
+    tile_size: UVec2,
+    #[serde(default = "default_padding")]
+    padding: UVec2,
+    #[serde(default)]
+    border_padding: Option<UVec2>,
+    #[serde(default = "default_offset")]
+    offset: UVec2,
+}
+
+impl From<TitanGridShorthand> for TitanEntry {
+    fn from(value: TitanGridShorthand) -> Self {
+        Self {
+            path: value.path,
+            sprite_sheet: TitanSpriteSheet::Grid {
+                tile_size: value.tile_size,
+                padding: value.padding,
+                border_padding: value.border_padding,
+                offset: value.offset,
+            },
+            ..Default::default()
+        }
+    }
+}
+
+/// Accepts the list of entries as well as the [`TitanGridShorthand`] for a single entry.
+fn deserialize_textures<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<TitanEntry>, D::Error> {
+    struct TexturesVisitor;
+
+    impl<'de> Visitor<'de> for TexturesVisitor {
+        type Value = Vec<TitanEntry>;
+
+        fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            formatter.write_str("a list of entries or a grid shorthand")
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+            Vec::deserialize(SeqAccessDeserializer::new(seq))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+            let grid = TitanGridShorthand::deserialize(MapAccessDeserializer::new(map))?;
+            Ok(vec![grid.into()])
+        }
+    }
+
+    deserializer.deserialize_any(TexturesVisitor)
+}
+
+/// Short names of common texture formats, accepted in addition to the names of [`TextureFormat`] in wgpu.
+const TEXTURE_FORMAT_ALIASES: &[(&str, TextureFormat)] = &[
+    ("rgba8-srgb", TextureFormat::Rgba8UnormSrgb),
+    ("rgba8", TextureFormat::Rgba8Unorm),
+    ("bgra8-srgb", TextureFormat::Bgra8UnormSrgb),
+    ("bgra8", TextureFormat::Bgra8Unorm),
+    ("rg8", TextureFormat::Rg8Unorm),
+    ("r8", TextureFormat::R8Unorm),
+    ("rgba16f", TextureFormat::Rgba16Float),
+    ("rgba32f", TextureFormat::Rgba32Float),
+    ("r32f", TextureFormat::R32Float),
+];
+
+/// The [`TextureFormat`] of an alias or of its name in wgpu, e.g. `rgba8unorm-srgb`.
+fn parse_texture_format(name: &str) -> Option<TextureFormat> {
+    TEXTURE_FORMAT_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == name)
+        .map(|(_, format)| *format)
+        .or_else(|| {
+            TextureFormat::deserialize(
+                IntoDeserializer::<serde::de::value::Error>::into_deserializer(name),
+            )
+            .ok()
+        })
+}
+
+/// Accepts a [`TextureFormat`] by its alias as well as by its name in wgpu.
+pub(crate) fn deserialize_texture_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<TextureFormat, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    parse_texture_format(&name)
+        .ok_or_else(|| D::Error::custom(format!("unknown texture format {name}")))
+}
+
+/// Like [`deserialize_texture_format`] for an optional one.
+pub(crate) fn deserialize_optional_texture_format<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<TextureFormat>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|name| {
+            parse_texture_format(&name)
+                .ok_or_else(|| D::Error::custom(format!("unknown texture format {name}")))
+        })
+        .transpose()
+}
+
+/// Writes [`TextureUsages`] as the names of its flags, e.g. `"TEXTURE_BINDING | COPY_DST"`.
+fn serialize_texture_usages<S: Serializer>(
+    texture_usages: &TextureUsages,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    texture_usages
+        .iter_names()
+        .map(|(name, _)| name)
+        .collect::<Vec<_>>()
+        .join(" | ")
+        .serialize(serializer)
+}
+
+/// Reads [`TextureUsages`] from the names of its flags, separated by `|`.
+fn deserialize_texture_usages<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<TextureUsages, D::Error> {
+    String::deserialize(deserializer)?
+        .split('|')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .try_fold(TextureUsages::empty(), |texture_usages, name| {
+            TextureUsages::from_name(name)
+                .map(|flag| texture_usages | flag)
+                .ok_or_else(|| D::Error::custom(format!("unknown texture usage {name}")))
+        })
+}
+
+/// Only the names of the configuration fields that are set in a titan ron file.
+#[derive(Deserialize)]
+struct TitanRonConfigurationFields {
+    #[serde(default = "ron_unit")]
+    configuration: ron::Value,
+}
+
+fn ron_unit() -> ron::Value {
+    ron::Value::Unit
+}
+
+/// Only the names of the configuration fields that are set in a titan json file.
+#[derive(Deserialize)]
+struct TitanJsonConfigurationFields {
+    #[serde(default)]
+    configuration: serde_json::Value,
+}
+
+/// Names of all configuration fields that are set in the titan file `bytes`.
+pub(crate) fn configuration_fields(
+    bytes: &[u8],
+    syntax: TitanSyntax,
+) -> Result<HashSet<String>, SpriteSheetLoaderError> {
+    let fields = match syntax {
+        TitanSyntax::Ron => {
+            let fields = syntax.deserialize::<TitanRonConfigurationFields>(bytes)?;
+            let ron::Value::Map(map) = fields.configuration else {
+                return Ok(HashSet::new());
+            };
+            map.keys()
+                .filter_map(|key| match key {
+                    ron::Value::String(key) => Some(key.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+        TitanSyntax::Json => {
+            let fields = syntax.deserialize::<TitanJsonConfigurationFields>(bytes)?;
+            let serde_json::Value::Object(map) = fields.configuration else {
+                return Ok(HashSet::new());
+            };
+            map.keys().cloned().collect()
+        }
+    };
+
+    Ok(fields)
+}
+
+/// Uses the configuration of the extended titan file `base_bytes` for every field that is not in `fields`
+/// and adds the fields set in the extended file. Returns what the extended file extends in turn.
+pub(crate) fn extend(
+    configuration: &mut TitanConfiguration,
+    fields: &mut HashSet<String>,
+    base_path: &str,
+    base_bytes: &[u8],
+) -> Result<Option<String>, SpriteSheetLoaderError> {
+    let syntax = TitanSyntax::from_path(Path::new(base_path));
+    let base = syntax.deserialize::<TitanBase>(base_bytes)?;
+    let mut base_configuration = base.configuration;
+    base_configuration.override_fields(configuration, fields);
+    *configuration = base_configuration;
+    fields.extend(configuration_fields(base_bytes, syntax)?);
+
+    Ok(base.extends)
+}
+
+/// Deterministic 64-bit FNV-1a hash over the bytes of a titan ron file and everything it reads.
+///
+/// Unlike the [`Hasher`](std::hash::Hasher)s of the standard library, the result is stable across
+/// platforms and compiler versions, so it can be persisted to decide whether an atlas has to be repacked.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Checksum(u64);
+
+impl Default for Checksum {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Checksum {
+    /// Hashes the length and the content of `bytes`, so that no two sequences of inputs collide by concatenation.
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    pub(crate) fn finish(self) -> u64 {
+        self.0
+    }
+}
+
+/// Policy for sprites whose texture format differs from the one of the combined texture atlas.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TitanFormatMismatch {
+    /// Silently convert the sprite, if `auto_format_conversion` is enabled.
+    #[default]
+    Convert,
+    /// Fail with a [`FormatMismatch`](crate::asset_loader::SpriteSheetLoaderError::FormatMismatch) error.
+    Error,
+    /// Log a warning and convert the sprite, if `auto_format_conversion` is enabled.
+    WarnAndConvert,
+}
+
+/// Corner of an image that the positions of rects and offsets are measured from.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TitanCoordinateOrigin {
+    /// y points down from the top left corner, like in bevy and most image editors.
+    #[default]
+    TopLeft,
+    /// y points up from the bottom left corner, like in engines with a bottom left origin.
+    ///
+    /// Positions are those of the bottom left corner of a rect, and grids start at the bottom row.
+    BottomLeft,
+}
+
+/// Kind of texture the sprites are combined into.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TitanOutput {
+    /// Pack all sprites into a single 2D texture atlas.
+    #[default]
+    Atlas,
+    /// Stack all sprites into a 2D array texture with one layer per sprite. All sprites must have the same size.
+    /// Every rect of the layout covers a whole layer, whose index is the index of the sprite.
+    Array,
+}
+
+/// Order in which the sprites are handed to the packing algorithm.
+/// The indices in the layout always follow the order of declaration.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TitanSortOrder {
+    /// Order of declaration.
+    #[default]
+    AsDeclared,
+    /// Biggest area first. Sprites of equal area keep their order of declaration.
+    BySizeDesc,
+    /// Ascending by name. Sprites without a name follow in their order of declaration.
+    ByName,
+}
+
+/// Sampler of the combined texture atlas.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+pub enum TitanSampler {
+    /// Use the default sampler of the `ImagePlugin`.
+    #[default]
+    Default,
+    /// Nearest neighbor filtering, suited for pixel art.
+    Nearest,
+    /// Linear filtering.
+    Linear,
+    /// Full [`ImageSamplerDescriptor`].
+    Descriptor(ImageSamplerDescriptor),
+}
+
+impl From<TitanSampler> for ImageSampler {
+    fn from(value: TitanSampler) -> Self {
+        match value {
+            TitanSampler::Default => ImageSampler::Default,
+            TitanSampler::Nearest => ImageSampler::nearest(),
+            TitanSampler::Linear => ImageSampler::linear(),
+            TitanSampler::Descriptor(descriptor) => ImageSampler::Descriptor(descriptor),
+        }
+    }
+}
+
+/// A single image asset and how it is interpreted.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TitanEntry {
+    /// Path to the image asset, relative to the assets folder. Empty if `data` is set.
+    #[serde(default)]
+    pub path: String,
+    /// Pixels of the image, embedded instead of referenced by `path`.
+    #[serde(default)]
+    pub data: Option<TitanData>,
+    /// Path to another titan ron file whose `frames` are pulled in instead of reading `path` or `data`.
+    #[serde(default)]
+    pub from_atlas: Option<String>,
+    /// Names of the sprites of `from_atlas` that are pulled in.
+    #[serde(default)]
+    pub frames: Vec<String>,
+    /// Pattern of image assets, e.g. `sprites/enemies/*.png`, that is expanded instead of reading `path` or `data`.
+    #[serde(default)]
+    pub glob: Option<String>,
+    /// Name of the sprite in the `names` asset.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The entry is only loaded if one of its tags is in `active_tags` of the loader settings. Untagged entries
+    /// are always loaded.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Every sprite of this entry is named with this prefix and its index within the entry appended, e.g. `frame0`.
+    #[serde(default)]
+    pub name_prefix: Option<String>,
+    /// Overrides `trim` of the [`TitanConfiguration`] for this entry.
+    #[serde(default)]
+    pub trim: Option<bool>,
+    /// Display duration in milliseconds for every sprite of this entry.
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+    /// Overrides `color_key` of the [`TitanConfiguration`] for this entry.
+    #[serde(default)]
+    pub color_key: Option<[u8; 3]>,
+    /// Overrides `auto_format_conversion` of the [`TitanConfiguration`] for this entry. If disabled, every sprite
+    /// of this entry must already have the texture format of the combined texture atlas.
+    #[serde(default)]
+    pub auto_format_conversion: Option<bool>,
+    /// Texture format every sprite of this entry is converted to right after extraction.
+    #[serde(default, deserialize_with = "deserialize_optional_texture_format")]
+    pub format: Option<TextureFormat>,
+    /// How a single channel image of this entry is replicated.
+    #[serde(default)]
+    pub channel_mapping: TitanChannelMapping,
+    /// Color space the pixels of this entry are encoded in. It has to match the texture format of the combined
+    /// texture atlas, so that sRGB and linear data are never packed into the same atlas.
+    #[serde(default)]
+    pub color_space: Option<TitanColorSpace>,
+    /// Factor every sprite of this entry is resampled by right after extraction.
+    #[serde(default)]
+    pub scale: Option<f32>,
+    /// Filter used to resample the sprites of this entry.
+    #[serde(default)]
+    pub scale_filter: TitanScaleFilter,
+    /// Anchor of every sprite of this entry, normalized from (0, 0) at the top left to (1, 1) at the bottom right.
+    #[serde(default)]
+    pub anchor: Option<Vec2>,
+    /// Nine-slice insets of every sprite of this entry.
+    #[serde(default)]
+    pub nine_slice: Option<TitanNineSlice>,
+    /// Also pack a mirrored copy of every sprite of this entry.
+    #[serde(default)]
+    pub flip: Option<TitanFlip>,
+    /// Mark every sprite of this entry as meant to be sampled with repeat wrapping. Tileable sprites are never trimmed.
+    #[serde(default)]
+    pub tileable: bool,
+    /// Region of the image asset the entry is cropped to before it is split into sprites.
+    #[serde(default)]
+    pub source_rect: Option<URect>,
+    /// Rotation of the image asset, applied after cropping to `source_rect` and before it is split into sprites.
+    #[serde(default)]
+    pub rotate: TitanRotation,
+    /// How the image asset is split into sprites.
+    #[serde(default)]
+    pub sprite_sheet: TitanSpriteSheet,
+}
+
+/// Insets in pixels from each edge of a sprite to its stretchable center.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TitanNineSlice {
+    /// Inset from the left edge.
+    pub left: u32,
+    /// Inset from the right edge.
+    pub right: u32,
+    /// Inset from the top edge.
+    pub top: u32,
+    /// Inset from the bottom edge.
+    pub bottom: u32,
+}
+
+impl TitanNineSlice {
+    /// Whether the insets leave a center of non-negative size in a sprite of `size`.
+    pub fn fits(&self, size: UVec2) -> bool {
+        self.left + self.right <= size.x && self.top + self.bottom <= size.y
+    }
+}
+
+impl From<TitanNineSlice> for BorderRect {
+    fn from(value: TitanNineSlice) -> Self {
+        BorderRect {
+            left: value.left as f32,
+            right: value.right as f32,
+            top: value.top as f32,
+            bottom: value.bottom as f32,
+        }
+    }
+}
+
+/// How the mirrored copies of the sprites of an entry are generated.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TitanFlip {
+    /// Mirror horizontally.
+    #[serde(default)]
+    pub x: bool,
+    /// Mirror vertically. Together with `x` this is a rotation by 180 degrees.
+    #[serde(default)]
+    pub y: bool,
+    /// Appended to the name of every mirrored sprite.
+    #[serde(default = "default_flip_suffix")]
+    pub suffix: String,
+}
+
+/// Clockwise rotation that corrects the orientation of an image asset.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TitanRotation {
+    /// Keep the image as it is.
+    #[default]
+    None,
+    /// Rotate by 90 degrees clockwise. Width and height are swapped.
+    Cw90,
+    /// Rotate by 180 degrees.
+    Cw180,
+    /// Rotate by 270 degrees clockwise. Width and height are swapped.
+    Cw270,
+}
+
+impl TitanRotation {
+    /// Size of an image of `size` after the rotation.
+    pub(crate) fn rotated_size(self, size: UVec2) -> UVec2 {
+        match self {
+            TitanRotation::None | TitanRotation::Cw180 => size,
+            TitanRotation::Cw90 | TitanRotation::Cw270 => UVec2::new(size.y, size.x),
+        }
+    }
+
+    /// Maps `rect` of the rotated image back onto the image of `size` before the rotation.
+    pub(crate) fn unrotate_rect(self, rect: URect, size: UVec2) -> URect {
+        let (min, max) = match self {
+            TitanRotation::None => (rect.min, rect.max),
+            TitanRotation::Cw90 => (
+                UVec2::new(rect.min.y, size.y - rect.max.x),
+                UVec2::new(rect.max.y, size.y - rect.min.x),
+            ),
+            TitanRotation::Cw180 => (size - rect.max, size - rect.min),
+            TitanRotation::Cw270 => (
+                UVec2::new(size.x - rect.max.y, rect.min.x),
+                UVec2::new(size.x - rect.min.y, rect.max.x),
+            ),
+        };
+        URect::from_corners(min, max)
+    }
+}
+
+/// How a single channel image is replicated when converted to a four channel format.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy)]
+pub enum TitanChannelMapping {
+    /// The channel is copied to red, green and blue. Alpha is opaque.
+    #[default]
+    Luminance,
+    /// The channel is copied to alpha. Red, green and blue are white.
+    Alpha,
+}
+
+/// Color space the pixels of an image are encoded in.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TitanColorSpace {
+    /// Gamma encoded color, e.g. albedo or UI textures.
+    Srgb,
+    /// Linear data, e.g. normal maps, masks or lookup tables.
+    Linear,
+}
+
+impl TitanColorSpace {
+    /// The color space of `format`.
+    pub fn of(format: TextureFormat) -> Self {
+        if format.is_srgb() {
+            Self::Srgb
+        } else {
+            Self::Linear
+        }
+    }
+
+    /// The variant of `format` with this color space, if there is one.
+    pub fn apply(self, format: TextureFormat) -> TextureFormat {
+        match self {
+            Self::Srgb => format.add_srgb_suffix(),
+            Self::Linear => format.remove_srgb_suffix(),
+        }
+    }
+}
+
+/// Pixels of an image that are embedded in the titan ron file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum TitanData {
+    /// Base64 encoded raw pixels of the given texture format and size, row by row without padding.
+    Inline(
+        String,
+        #[serde(deserialize_with = "deserialize_texture_format")] TextureFormat,
+        UVec2,
+    ),
+}
+
+/// Filter used to resample a sprite.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TitanScaleFilter {
+    /// Use the nearest pixel, suited for pixel art.
+    #[default]
+    Nearest,
+    /// Interpolate between the four nearest pixels.
+    Bilinear,
+}
+
+/// How an image asset is split into sprites.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub enum TitanSpriteSheet {
+    /// The image asset is a single sprite.
+    #[default]
+    None,
+    /// The image asset is a sprite sheet of equally sized tiles.
+    Homogeneous {
+        /// Size of a single tile.
+        tile_size: UVec2,
+        /// Number of columns.
+        columns: u32,
+        /// Number of rows.
+        rows: u32,
+        /// Padding around every tile. Two neighboring tiles are twice the padding apart.
+        #[serde(default = "default_padding")]
+        padding: UVec2,
+        /// Padding on every side of every tile, for sheets whose tiles are not padded equally on both sides.
+        /// Replaces `padding` if set.
+        #[serde(default)]
+        padding_rect: Option<TitanPaddingRect>,
+        /// Padding along the border of the sprite sheet. Defaults to `padding`, or to the left and top
+        /// side of `padding_rect`.
+        #[serde(default)]
+        border_padding: Option<UVec2>,
+        /// Offset of the first tile from the top left corner.
+        #[serde(default = "default_offset")]
+        offset: UVec2,
+    },
+    /// The image asset is a sprite sheet of arbitrary rects.
+    #[serde(
+        serialize_with = "serialize_heterogeneous",
+        deserialize_with = "deserialize_heterogeneous"
+    )]
+    Heterogeneous {
+        /// All sprites of the sprite sheet.
+        rects: Vec<TitanRect>,
+        /// Offset that is added to the position of every rect.
+        #[serde(default = "default_offset")]
+        offset: UVec2,
+    },
+    /// Like [`TitanSpriteSheet::Heterogeneous`], but the rects do not have to be aligned to whole pixels.
+    ///
+    /// Every sprite is resampled with bilinear filtering to the rounded size of its rect.
+    HeterogeneousSubpixel {
+        /// All sprites of the sprite sheet.
+        rects: Vec<TitanSubpixelRect>,
+        /// Offset that is added to the position of every rect.
+        #[serde(default)]
+        offset: Vec2,
+    },
+    /// Like [`TitanSpriteSheet::Homogeneous`], but columns and rows are derived from the image size.
+    Grid {
+        /// Size of a single tile.
+        tile_size: UVec2,
+        /// Padding around every tile. Two neighboring tiles are twice the padding apart.
+        #[serde(default = "default_padding")]
+        padding: UVec2,
+        /// Padding along the border of the sprite sheet. Defaults to `padding`.
+        #[serde(default)]
+        border_padding: Option<UVec2>,
+        /// Offset of the first tile from the top left corner.
+        #[serde(default = "default_offset")]
+        offset: UVec2,
+    },
+    /// Experimental: the cells of the sprite sheet are separated by gutters of a single color.
+    ///
+    /// Every full row and column of the gutter color separates cells, so cells do not need to have the same size.
+    ColorGrid {
+        /// RGB color of the gutters.
+        gutter_color: [u8; 3],
+        /// Maximum difference to `gutter_color` in every channel for a pixel to still be part of a gutter.
+        #[serde(default)]
+        tolerance: u8,
+    },
+}
+
+/// Padding on every side of a tile of a [`TitanSpriteSheet::Homogeneous`] sprite sheet.
+///
+/// Two horizontally neighboring tiles are `right` plus `left` apart, two vertically neighboring ones
+/// `bottom` plus `top`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TitanPaddingRect {
+    /// Padding to the left of every tile.
+    #[serde(default)]
+    pub left: u32,
+    /// Padding above every tile.
+    #[serde(default)]
+    pub top: u32,
+    /// Padding to the right of every tile.
+    #[serde(default)]
+    pub right: u32,
+    /// Padding below every tile.
+    #[serde(default)]
+    pub bottom: u32,
+}
+
+/// A single sprite of a heterogeneous sprite sheet.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(from = "TitanRectDef", into = "TitanRectDef")]
+pub struct TitanRect {
+    /// Name of the sprite in the `names` asset.
+    pub name: Option<String>,
+    /// Top left corner of the sprite.
+    pub position: UVec2,
+    /// Size of the sprite.
+    pub size: UVec2,
+    /// Overrides `anchor` of the [`TitanEntry`] for this sprite.
+    pub anchor: Option<Vec2>,
+}
+
+/// A single sprite of a [`TitanSpriteSheet::HeterogeneousSubpixel`] sprite sheet.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TitanSubpixelRect {
+    /// Name of the sprite in the `names` asset.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Top left corner of the sprite in pixels.
+    pub position: Vec2,
+    /// Size of the sprite in pixels.
+    pub size: Vec2,
+    /// Overrides `anchor` of the [`TitanEntry`] for this sprite.
+    #[serde(default)]
+    pub anchor: Option<Vec2>,
+}
+
+impl TitanSubpixelRect {
+    /// The rect within the image, with `offset` added to its position.
+    pub fn rect(&self, offset: Vec2) -> Rect {
+        let min = self.position + offset;
+        Rect::from_corners(min, min + self.size)
+    }
+}
+
+/// Accepts the named struct as well as the plain `(position, size)` tuple.
+/// Rects without name and anchor are written as tuple.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum TitanRectDef {
+    Tuple(UVec2, UVec2),
+    Struct {
+        #[serde(default)]
+        name: Option<String>,
+        position: UVec2,
+        size: UVec2,
+        #[serde(default)]
+        anchor: Option<Vec2>,
+    },
+}
+
+impl From<TitanRectDef> for TitanRect {
+    fn from(value: TitanRectDef) -> Self {
+        match value {
+            TitanRectDef::Tuple(position, size) => Self {
+                name: None,
+                position,
+                size,
+                anchor: None,
+            },
+            TitanRectDef::Struct {
+                name,
+                position,
+                size,
+                anchor,
+            } => Self {
+                name,
+                position,
+                size,
+                anchor,
+            },
+        }
+    }
+}
+
+impl From<TitanRect> for TitanRectDef {
+    fn from(value: TitanRect) -> Self {
+        match (value.name, value.anchor) {
+            (None, None) => Self::Tuple(value.position, value.size),
+            (name, anchor) => Self::Struct {
+                name,
+                position: value.position,
+                size: value.size,
+                anchor,
+            },
+        }
+    }
+}
+
+/// Accepts the plain list of rects as well as the rects with an offset.
+/// Rects without offset are written as list.
+#[derive(Serialize, Deserialize)]
+#[serde(untagged, deny_unknown_fields)]
+enum TitanHeterogeneousDef {
+    List(Vec<TitanRect>),
+    Struct {
+        rects: Vec<TitanRect>,
+        #[serde(default = "default_offset")]
+        offset: UVec2,
+    },
+}
+
+/// Writes the fields of [`TitanSpriteSheet::Heterogeneous`] as [`TitanHeterogeneousDef`].
+fn serialize_heterogeneous<S: Serializer>(
+    rects: &[TitanRect],
+    offset: &UVec2,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let rects = rects.to_vec();
+    if *offset == default_offset() {
+        TitanHeterogeneousDef::List(rects)
+    } else {
+        TitanHeterogeneousDef::Struct {
+            rects,
+            offset: *offset,
+        }
+    }
+    .serialize(serializer)
+}
+
+/// Reads the fields of [`TitanSpriteSheet::Heterogeneous`] from [`TitanHeterogeneousDef`].
+fn deserialize_heterogeneous<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<(Vec<TitanRect>, UVec2), D::Error> {
+    Ok(match TitanHeterogeneousDef::deserialize(deserializer)? {
+        TitanHeterogeneousDef::List(rects) => (rects, default_offset()),
+        TitanHeterogeneousDef::Struct { rects, offset } => (rects, offset),
+    })
+}
+
+/// A named animation.
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TitanAnimation {
+    /// Name of the animation in the `animations` asset.
+    pub name: String,
+    /// Sprites in playback order, referenced by their index in the layout or by their name.
+    pub frames: Vec<TitanFrame>,
+    /// Display duration in milliseconds of every frame. Falls back to `duration_ms` of the entry.
+    #[serde(default)]
+    pub duration_ms: Option<u32>,
+    /// Frames per second for every frame that has no duration otherwise.
+    #[serde(default)]
+    pub frame_rate: Option<f32>,
+    /// How the frames are meant to be played back.
+    #[serde(default)]
+    pub playback: TitanPlayback,
+}
+
+/// A sprite that is a frame of a [`TitanAnimation`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum TitanFrame {
+    /// Index of the sprite in the layout.
+    Index(usize),
+    /// Name of the sprite in the `names` asset.
+    Name(String),
+}
+
+impl From<usize> for TitanFrame {
+    fn from(value: usize) -> Self {
+        Self::Index(value)
+    }
+}
+
+impl From<&str> for TitanFrame {
+    fn from(value: &str) -> Self {
+        Self::Name(value.to_string())
+    }
+}
+
+/// How the frames of an animation are meant to be played back.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TitanPlayback {
+    /// Play the frames in order, whether to repeat is left to the player.
+    #[default]
+    Forward,
+    /// Play the frames in order and start over.
+    Loop,
+    /// Play the frames in order and hold the last one.
+    Once,
+    /// Play the frames in order and back again, then start over. The frames of the way back are appended
+    /// in the `animations` asset, without repeating the first and the last frame.
+    PingPong,
+}
+
+impl From<TitanPlayback> for AnimationPlayback {
+    fn from(value: TitanPlayback) -> Self {
+        match value {
+            TitanPlayback::Forward => AnimationPlayback::Forward,
+            TitanPlayback::Loop => AnimationPlayback::Loop,
+            TitanPlayback::Once => AnimationPlayback::Once,
+            TitanPlayback::PingPong => AnimationPlayback::PingPong,
+        }
+    }
+}
+
+#[inline]
+const fn default_initial_size() -> UVec2 {
+    UVec2::new(256, 256)
+}
+
+#[inline]
+const fn default_texture_usages() -> TextureUsages {
+    /* Same as every image that bevy creates */
+    TextureUsages::TEXTURE_BINDING.union(TextureUsages::COPY_DST)
+}
+
+const fn default_alignment() -> u32 {
+    1
+}
+
+#[inline]
+const fn default_max_size() -> UVec2 {
+    UVec2::new(2048, 2048)
+}
+
+#[inline]
+const fn default_format() -> TextureFormat {
+    TextureFormat::Rgba8UnormSrgb
+}
+
+#[inline]
+const fn default_auto_format_conversion() -> bool {
+    true
+}
+
+#[inline]
+const fn default_always_pack() -> bool {
+    true
+}
+
+#[inline]
+const fn default_repack() -> bool {
+    true
+}
+
+#[inline]
+const fn default_warn_on_overlap() -> bool {
+    true
+}
+
+#[inline]
+const fn default_max_frames() -> u64 {
+    65536
+}
+
+#[inline]
+const fn default_max_total_area() -> u64 {
+    8192 * 8192
+}
+
+#[inline]
+fn default_flip_suffix() -> String {
+    "_flipped".to_string()
+}
+
+#[inline]
+const fn default_padding() -> UVec2 {
+    UVec2::ZERO
+}
+
+#[inline]
+const fn default_offset() -> UVec2 {
+    UVec2::ZERO
+}
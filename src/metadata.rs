@@ -0,0 +1,275 @@
+//! This module defines the metadata assets that are emitted alongside the texture atlas of a titan ron file.
+//!
+//! Every metadata asset is indexed the same way as the [`TextureAtlasLayout`](::bevy::sprite::TextureAtlasLayout)
+//! and can be loaded via its label, e.g. `"example.titan.ron#names"`.
+
+use std::{ops::Range, time::Duration};
+
+use bevy::{
+    asset::Asset,
+    math::{Rect, URect, UVec2, Vec2},
+    prelude::{Deref, DerefMut},
+    reflect::Reflect,
+    sprite::{Anchor, BorderRect, TextureAtlasLayout},
+    utils::HashMap,
+};
+use serde::{Deserialize, Serialize};
+
+/// Maps sprite names to their index in the texture atlas layout.
+///
+/// Loaded with the `names` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasNames(pub HashMap<String, usize>);
+
+/// Trim information of a single sprite.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub struct SpriteTrim {
+    /// Size of the sprite before trimming.
+    pub original_size: UVec2,
+    /// Position of the trimmed sprite within the untrimmed sprite.
+    pub offset: UVec2,
+}
+
+impl SpriteTrim {
+    /// Translation of the center of the trimmed sprite from the center of the untrimmed sprite, with y pointing up.
+    ///
+    /// `trimmed_size` is the size of the rect of the sprite in the texture atlas layout. Add this to the translation
+    /// of a centered sprite to render it at its original position, so animation frames do not jitter.
+    pub fn render_offset(&self, trimmed_size: UVec2) -> Vec2 {
+        let offset = self.offset.as_vec2() + trimmed_size.as_vec2() / 2.0
+            - self.original_size.as_vec2() / 2.0;
+        Vec2::new(offset.x, -offset.y)
+    }
+
+    /// The bevy sprite [`Anchor`] of the trimmed sprite that corresponds to `anchor` of the untrimmed sprite.
+    ///
+    /// `anchor` is normalized like the [`TextureAtlasAnchors`], e.g. (0.5, 0.5) keeps the center of the untrimmed
+    /// sprite at the translation of the entity, no matter how the sprite was trimmed.
+    pub fn anchor(&self, anchor: Vec2, trimmed_size: UVec2) -> Anchor {
+        let anchor = (anchor * self.original_size.as_vec2() - self.offset.as_vec2())
+            / trimmed_size.as_vec2();
+        Anchor::Custom(Vec2::new(anchor.x - 0.5, 0.5 - anchor.y))
+    }
+}
+
+/// Trim information for every sprite in the texture atlas layout.
+///
+/// Sprites that were not trimmed have their own size as `original_size` and a zero `offset`.
+/// Loaded with the `trim` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasTrims(pub Vec<SpriteTrim>);
+
+/// A single frame of an animation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub struct AnimationFrame {
+    /// Index of the sprite in the texture atlas layout.
+    pub index: usize,
+    /// How long the sprite is displayed.
+    pub duration: Duration,
+}
+
+/// How the frames of an [`AnimationClip`] are meant to be played back.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum AnimationPlayback {
+    /// Play the frames in order, whether to repeat is left to the player.
+    #[default]
+    Forward,
+    /// Play the frames in order and start over.
+    Loop,
+    /// Play the frames in order and hold the last one.
+    Once,
+    /// Start over after the last frame. The frames already go forth and back.
+    PingPong,
+}
+
+/// Frames of a named animation and how they are meant to be played back.
+///
+/// Dereferences to its frames.
+#[derive(Debug, Default, Clone, PartialEq, Reflect, Deref, DerefMut)]
+pub struct AnimationClip {
+    /// Frames in playback order.
+    #[deref]
+    pub frames: Vec<AnimationFrame>,
+    /// How the frames are meant to be played back.
+    pub playback: AnimationPlayback,
+}
+
+/// Animation made up of all sprites that have a duration, in the order they are declared.
+///
+/// Loaded with the `animation` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasAnimation(pub Vec<AnimationFrame>);
+
+/// Maps animation names to their frames.
+///
+/// Loaded with the `animations` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasAnimations(pub HashMap<String, AnimationClip>);
+
+/// Whether each sprite is rotated by 90 degrees clockwise within the texture atlas.
+///
+/// Only sprites of a titan ron file with `allow_rotation` can be rotated. Their rect in the texture atlas layout
+/// has width and height swapped, so rotate the sprite by 90 degrees counterclockwise to display it upright.
+/// Loaded with the `rotation` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasRotations(pub Vec<bool>);
+
+/// Anchor of every sprite, normalized from (0, 0) at the top left to (1, 1) at the bottom right of the untrimmed sprite.
+///
+/// Sprites without an explicit anchor are anchored at their center (0.5, 0.5).
+/// Loaded with the `anchors` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasAnchors(pub Vec<Vec2>);
+
+impl TextureAtlasAnchors {
+    /// The anchor of the sprite at `index` as a bevy sprite [`Anchor`].
+    pub fn sprite_anchor(&self, index: usize) -> Option<Anchor> {
+        self.0
+            .get(index)
+            .map(|anchor| Anchor::Custom(Vec2::new(anchor.x - 0.5, 0.5 - anchor.y)))
+    }
+}
+
+/// Whether each sprite is meant to be sampled with repeat wrapping.
+///
+/// The texture atlas itself can not be sampled with repeat wrapping, so the UVs of such a sprite have to be
+/// wrapped into its rect manually, e.g. with `fract` in a shader. Linear filtering still samples the padding
+/// at its right and bottom edge, which `extrude_edges` fills with the opposite edge of tileable sprites.
+/// Loaded with the `tileable` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasTileables(pub Vec<bool>);
+
+/// Region of the source image a single sprite was extracted from.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Reflect)]
+pub struct SpriteSource {
+    /// Path of the source image, or a placeholder for inline data.
+    pub path: String,
+    /// Rect of the sprite within the source image, before scaling, trimming and packing.
+    pub rect: URect,
+    /// Whether the sprite differs from its source region, because it was flipped, rotated, scaled, trimmed,
+    /// color keyed or converted.
+    pub transformed: bool,
+}
+
+/// Source region of every sprite, e.g. to draw a debug overlay on the source images.
+///
+/// Flipped copies share the source region of their sprite.
+/// Loaded with the `source_rects` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasSourceRects(pub Vec<SpriteSource>);
+
+/// Maps the path of every source image to the ranges of indices of the sprites it produced, in declaration order.
+///
+/// An image that is used by multiple entries has one range per entry, unless they are adjacent.
+/// Flipped copies are part of the range of their entry. Inline images are not included.
+/// Loaded with the `sources` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasSources(pub HashMap<String, Vec<Range<usize>>>);
+
+impl TextureAtlasSources {
+    /// All indices of the sprites produced by the image at `path`.
+    pub fn indices(&self, path: &str) -> impl Iterator<Item = usize> + '_ {
+        self.0.get(path).into_iter().flatten().cloned().flatten()
+    }
+}
+
+/// Sprites produced by a single entry of a titan ron file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Reflect)]
+pub struct SpriteGroup {
+    /// `name` of the entry.
+    pub name: Option<String>,
+    /// Indices of all sprites of the entry, including flipped copies.
+    pub indices: Range<usize>,
+}
+
+/// The sprites of every entry in declaration order, e.g. to build an animation per entry.
+///
+/// Entries are counted after tags, globs and missing images are resolved.
+/// Loaded with the `groups` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasGroups(pub Vec<SpriteGroup>);
+
+impl TextureAtlasGroups {
+    /// Indices of the sprites of the entry with the given `name`.
+    pub fn indices(&self, name: &str) -> Option<Range<usize>> {
+        self.0
+            .iter()
+            .find(|group| group.name.as_deref() == Some(name))
+            .map(|group| group.indices.clone())
+    }
+}
+
+/// UV rect of every sprite, normalized from (0, 0) at the top left to (1, 1) at the bottom right of its page,
+/// e.g. for custom shaders. Rotated sprites are not rotated back.
+///
+/// Loaded with the `uvs` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasUvs(pub Vec<Rect>);
+
+/// Nine-slice insets of every sprite, relative to the untrimmed sprite.
+///
+/// Sprites without nine-slice insets are `None`. Use with a [`TextureSlicer`](::bevy::sprite::TextureSlicer).
+/// Loaded with the `slices` label.
+#[derive(Debug, Default, Clone, Asset, Reflect, Deref, DerefMut)]
+pub struct TextureAtlasSlices(pub Vec<Option<BorderRect>>);
+
+/// How efficiently the sprites are packed into the pages of the texture atlas, e.g. to tune `initial_size`,
+/// `max_size` and `padding`.
+///
+/// Loaded with the `stats` label.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Asset, Reflect)]
+pub struct TextureAtlasStats {
+    /// Number of pixels covered by sprites. Sprites that share a rect, e.g. duplicates, are counted once.
+    pub used_area: u64,
+    /// Number of pixels of all pages.
+    pub atlas_area: u64,
+    /// Number of pages.
+    pub page_count: usize,
+    /// `used_area` divided by `atlas_area`, from 0 to 1.
+    pub efficiency: f32,
+}
+
+impl TextureAtlasStats {
+    /// Number of pixels that are not covered by any sprite, e.g. padding and free space.
+    pub fn wasted_area(&self) -> u64 {
+        self.atlas_area - self.used_area
+    }
+}
+
+/// Size, rect and page of every sprite of a texture atlas, e.g. to snapshot test the layout of a titan ron file
+/// and catch accidental repacks.
+///
+/// Serialize it to ron once and compare every later result against it.
+/// Use [`TextureAtlas::layout_snapshot`](crate::asset_loader::TextureAtlas::layout_snapshot) for a loaded texture atlas.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutSnapshot {
+    /// Size of the layout.
+    pub size: UVec2,
+    /// Rect of every sprite, indexed like the layout.
+    pub rects: Vec<URect>,
+    /// Page of every sprite, indexed like the layout.
+    pub page_indices: Vec<usize>,
+}
+
+impl LayoutSnapshot {
+    /// Indices of all sprites whose rect or page differs from `other`, including sprites only one of them has.
+    pub fn changed_indices(&self, other: &Self) -> Vec<usize> {
+        (0..self.rects.len().max(other.rects.len()))
+            .filter(|&index| {
+                self.rects.get(index) != other.rects.get(index)
+                    || self.page_indices.get(index) != other.page_indices.get(index)
+            })
+            .collect()
+    }
+}
+
+impl From<&TextureAtlasLayout> for LayoutSnapshot {
+    /// Snapshot of a layout with a single page.
+    fn from(layout: &TextureAtlasLayout) -> Self {
+        Self {
+            size: layout.size,
+            rects: layout.textures.clone(),
+            page_indices: vec![0; layout.len()],
+        }
+    }
+}